@@ -0,0 +1,182 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use crate::{util, Context};
+
+/// Generate a companion struct that holds the content of all variants of an enum.
+///
+/// This generates:
+/// * a tag enum with a unit variant for each variant of the enum, unless the enum has `#[serde(tag_enum = "...")]`,
+/// * a payload struct for each variant with content,
+/// * the companion struct with the tag field and an optional field for the content of each variant,
+/// * conversions between the enum and the companion struct.
+pub fn impl_all_variants(context: &mut Context, item: crate::input::Enum) -> TokenStream {
+	if let Some(variant) = item.catch_all_variant() {
+		context.spanned_error(
+			&variant.ident,
+			"#[derive(AllVariants)] does not support #[serde(catch_all)] variants",
+		);
+		return TokenStream::new();
+	}
+
+	let enum_name = &item.ident;
+	let vis = &item.vis;
+	let struct_name = all_variants_name(context, &item);
+	let tag_field_name = super::tag_field_name(context, &item);
+	let tag_ident = field_ident(&tag_field_name, || format_ident!("tag"));
+
+	// Use the tag enum from `#[derive(TagEnum)]` if there is one.
+	let (tag_name, tag_enum) = match super::tag_enum_name(context, &item) {
+		Some(tag_name) => (tag_name, None),
+		None => {
+			let tag_name = format_ident!("{}Tag", struct_name);
+			let tag_enum = super::tag_enum::make_tag_enum(context, &item, &tag_name);
+			(tag_name, Some(tag_enum))
+		},
+	};
+
+	let mut payload_structs = Vec::new();
+	let mut fields = Vec::new();
+	let mut into_arms = Vec::new();
+	let mut from_arms = Vec::new();
+	for variant in &item.variants {
+		let variant_name = &variant.ident;
+		let fields_expression = super::fields_expression(&variant.fields);
+
+		// Unit variants do not have a payload.
+		if super::is_unit_like(&item, variant) {
+			into_arms.push((variant_name, None));
+			from_arms.push(quote! {
+				#tag_name::#variant_name => ::core::result::Result::Ok(Self::#variant_name #fields_expression),
+			});
+			continue;
+		}
+
+		let content_name = super::variant_content_name(&item, variant);
+		let content_aliases = super::variant_content_aliases(&item, variant);
+		let payload_name = format_ident!("{}{}Data", enum_name, variant_name);
+		let field_ident = field_ident(&content_name, || {
+			let name = util::RenameRule::SnakeCase.apply_to_variant(&variant_name.to_string());
+			format_ident!("{}", name)
+		});
+
+		let generics = util::prune_generics(&item.generics, variant.fields.iter_types());
+		let (_impl_generics, type_generics, _where_clause) = generics.split_for_impl();
+		// The `Data` structs of the `Serialize` and `Deserialize` derives can not be reused here:
+		// they are local to the generated functions, and the `Serialize` one borrows its fields.
+		payload_structs.push(super::payload_struct(context, &item, variant, &payload_name));
+
+		let doc = format!(" The content of the `{variant_name}` variant.");
+		fields.push(quote! {
+			#[doc = #doc]
+			#[serde(rename = #content_name)]
+			#(#[serde(alias = #content_aliases)])*
+			#[serde(default, skip_serializing_if = "::core::option::Option::is_none")]
+			#vis #field_ident: ::core::option::Option<#payload_name #type_generics>,
+		});
+		into_arms.push((variant_name, Some((field_ident.clone(), payload_name.clone()))));
+		let internal = &context.internal;
+		from_arms.push(quote! {
+			#tag_name::#variant_name => match value.#field_ident {
+				::core::option::Option::Some(#payload_name #fields_expression) => ::core::result::Result::Ok(Self::#variant_name #fields_expression),
+				::core::option::Option::None => ::core::result::Result::Err(#internal::MissingContent::new(#content_name)),
+			},
+		});
+	}
+
+	// Set the content of the active variant and leave the rest empty.
+	let field_idents: Vec<_> = into_arms
+		.iter()
+		.filter_map(|(_, payload)| payload.as_ref().map(|(ident, _)| ident))
+		.collect();
+	let into_arms: Vec<_> = item
+		.variants
+		.iter()
+		.zip(&into_arms)
+		.map(|(variant, (variant_name, payload))| {
+			let fields_expression = super::fields_expression(&variant.fields);
+			let values = field_idents.iter().map(|ident| match payload {
+				Some((active, payload_name)) if active == *ident => {
+					quote!(#ident: ::core::option::Option::Some(#payload_name #fields_expression))
+				},
+				_ => quote!(#ident: ::core::option::Option::None),
+			});
+			quote! {
+				#enum_name::#variant_name #fields_expression => Self {
+					#tag_ident: #tag_name::#variant_name,
+					#(#values,)*
+				},
+			}
+		})
+		.collect();
+
+	let (impl_generics, type_generics, where_clause) = item.generics.split_for_impl();
+	let derives = super::payload_derives(context, &item);
+	let rename = &item.attr.rename;
+	let deny_unknown_fields = &item.attr.deny_unknown_fields;
+	let internal = &context.internal;
+	let struct_doc = format!(" The content of all variants of [`{enum_name}`], generated by `#[derive(AllVariants)]`.");
+	let tag_doc = format!(" The active variant of [`{enum_name}`].");
+
+	quote! {
+		#tag_enum
+
+		#(#payload_structs)*
+
+		#[doc = #struct_doc]
+		#derives
+		#rename
+		#deny_unknown_fields
+		#vis struct #struct_name #impl_generics #where_clause {
+			#[doc = #tag_doc]
+			#[serde(rename = #tag_field_name)]
+			#vis #tag_ident: #tag_name,
+			#(#fields)*
+		}
+
+		#[automatically_derived]
+		impl #impl_generics ::core::convert::From<#enum_name #type_generics> for #struct_name #type_generics #where_clause {
+			fn from(value: #enum_name #type_generics) -> Self {
+				match value {
+					#(#into_arms)*
+				}
+			}
+		}
+
+		#[automatically_derived]
+		impl #impl_generics ::core::convert::TryFrom<#struct_name #type_generics> for #enum_name #type_generics #where_clause {
+			type Error = #internal::MissingContent;
+
+			fn try_from(value: #struct_name #type_generics) -> ::core::result::Result<Self, Self::Error> {
+				match value.#tag_ident {
+					#(#from_arms)*
+				}
+			}
+		}
+	}
+}
+
+/// Compute the name of the companion struct.
+///
+/// This is the value of `#[serde(all_variants = "...")]`, or the name of the enum followed by `AllVariants`.
+fn all_variants_name(context: &mut Context, item: &crate::input::Enum) -> syn::Ident {
+	item.attr
+		.all_variants
+		.as_ref()
+		.and_then(|name| super::item_name(context, name))
+		.unwrap_or_else(|| format_ident!("{}AllVariants", item.ident))
+}
+
+/// Create an identifier for a struct field from the serialized name of the field.
+///
+/// Keywords are turned into raw identifiers.
+/// If the name is not a valid identifier at all, the fallback is used.
+fn field_ident(name: &str, fallback: impl FnOnce() -> syn::Ident) -> syn::Ident {
+	if let Ok(ident) = syn::parse_str(name) {
+		ident
+	} else if let Ok(ident) = syn::parse_str(&format!("r#{name}")) {
+		ident
+	} else {
+		fallback()
+	}
+}
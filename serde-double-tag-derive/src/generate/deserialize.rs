@@ -1,6 +1,7 @@
-use proc_macro2::{Span, TokenStream};
+use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 
+use crate::input::attributes::UnitContentKind;
 use crate::{util, Context};
 
 /// Generate code that implement the serde `Deserialize` trait for an enum using the double-tag format.
@@ -8,39 +9,24 @@ pub fn impl_deserialize_enum(context: &mut Context, item: crate::input::Enum) ->
 	let enum_name = &item.ident;
 	let tag_field_name = super::tag_field_name(context, &item);
 
-	let variant_name: Vec<_> = item.variants.iter().map(|variant| &variant.ident).collect();
-	let variant_deserialize: Vec<_> = item
+	let match_arms: Vec<_> = item
 		.variants
 		.iter()
 		.map(|variant| {
-			let variant_name = &variant.ident;
-			let variant_tag_value = super::variant_tag_value(&item, variant);
-			let data = make_data_struct(context, &item, variant);
-			let fields = super::fields_expression(&variant.fields);
-			let deny_unknown_fields = item.attr.deny_unknown_fields.is_some();
-
-			let function = match variant.fields {
-				crate::input::Fields::Unit => {
-					proc_macro2::Ident::new("deserialize_variant_optional", Span::call_site())
-				},
-				crate::input::Fields::Tuple(_) => {
-					proc_macro2::Ident::new("deserialize_variant_required", Span::call_site())
-				},
-				crate::input::Fields::Struct(_) => {
-					proc_macro2::Ident::new("deserialize_variant_required", Span::call_site())
-				},
-			};
+			let body = make_variant_body(context, &item, variant, &tag_field_name);
 
-			let internal = &context.internal;
+			// The `#[serde(catch_all)]` variant captures the tag and the content stored under the tag value.
+			if variant.attr.catch_all.is_some() {
+				let internal = &context.internal;
+				return quote! {
+					#internal::DeserializedTag::Unknown(field_tag) => #body,
+				};
+			}
 
+			let internal = &context.internal;
+			let variant_name = &variant.ident;
 			quote! {
-				#data
-				const FIELD_NAMES: #internal::FieldNames = #internal::FieldNames {
-					tag: #tag_field_name,
-					content: #variant_tag_value,
-				};
-				let Data #fields = #internal::#function(&FIELD_NAMES, map, #deny_unknown_fields)?;
-				Ok(Self::Value::#variant_name #fields)
+				#internal::DeserializedTag::Known(Tag::#variant_name) => #body,
 			}
 		})
 		.collect();
@@ -50,12 +36,35 @@ pub fn impl_deserialize_enum(context: &mut Context, item: crate::input::Enum) ->
 	let (impl_generics, _type_generics, _where_clause) = de_generics.split_for_impl();
 	let where_clause = make_where_clause(context, &item, &de_lifetime);
 
-	let tag_enum = make_tag_enum(context, &item);
+	let tag_enum = make_tag_enum(&item);
+	let enum_info = make_enum_info(context, &item, &tag_field_name);
+	let validate = make_validate(context, &item, &de_lifetime);
+	let validate_arg = match validate {
+		Some(_) => quote!(::core::option::Option::Some(&validate)),
+		None => quote!(::core::option::Option::None),
+	};
+
+	// Without a `#[serde(catch_all)]` variant, unknown tags are never captured.
+	let unknown_arm = match item.catch_all_variant() {
+		Some(_) => None,
+		None => {
+			let internal = &context.internal;
+			Some(quote! {
+				#internal::DeserializedTag::Unknown(_) => ::core::unreachable!(),
+			})
+		},
+	};
+
+	let content_fields = make_content_fields_impl(context, &item);
+	let deserialize_as = make_deserialize_as(context, &item, &tag_field_name);
 
 	let internal = &context.internal;
 	let serde = &context.serde;
 
 	quote! {
+		#content_fields
+		#deserialize_as
+
 		#[automatically_derived]
 		impl #impl_generics  #serde::Deserialize<#de_lifetime> for #enum_name #type_generics #where_clause {
 			fn deserialize<D: #serde::Deserializer<#de_lifetime>>(deserializer: D) -> ::core::result::Result<Self, D::Error> {
@@ -69,16 +78,15 @@ pub fn impl_deserialize_enum(context: &mut Context, item: crate::input::Enum) ->
 						f.write_str("map with `type` and data fields")
 					}
 
-					fn visit_map<A: #serde::de::MapAccess<#de_lifetime>>(self, mut map: A) -> ::core::result::Result<Self::Value, A::Error> {
+					fn visit_map<A: #serde::de::MapAccess<#de_lifetime>>(self, map: A) -> ::core::result::Result<Self::Value, A::Error> {
 						#tag_enum
+						#enum_info
 
-						let tag: Tag = #internal::deserialize_tag(#tag_field_name, &mut map)?;
+						#validate
+						let (tag, remaining) = #internal::deserialize_tag(&ENUM_INFO, map, #validate_arg)?;
 						match tag {
-							#(
-								Tag::#variant_name => {
-									#variant_deserialize
-								},
-							)*
+							#(#match_arms)*
+							#unknown_arm
 						}
 					}
 				}
@@ -91,6 +99,167 @@ pub fn impl_deserialize_enum(context: &mut Context, item: crate::input::Enum) ->
 	}
 }
 
+/// Generate the `deserialize_as()` function that deserializes a specific variant, ignoring the tag field.
+///
+/// Returns `None` if the enum does not have `#[serde(tag_enum = "...")]`, since the variant is selected with the tag enum.
+fn make_deserialize_as(context: &mut Context, item: &crate::input::Enum, tag_field_name: &str) -> Option<TokenStream> {
+	let tag_enum_name = super::tag_enum_name(context, item)?;
+
+	// The `#[serde(catch_all)]` variant is not part of the tag enum.
+	let match_arms: Vec<_> = item
+		.variants
+		.iter()
+		.filter(|variant| variant.attr.catch_all.is_none())
+		.map(|variant| {
+			let body = make_variant_body(context, item, variant, tag_field_name);
+			let variant_name = &variant.ident;
+			quote! {
+				#tag_enum_name::#variant_name => #body,
+			}
+		})
+		.collect();
+
+	let enum_name = &item.ident;
+	let vis = &item.vis;
+	let (impl_generics, type_generics, where_clause) = item.generics.split_for_impl();
+	let (de_generics, de_lifetime) = util::add_lifetime(context, &item.generics, "de");
+	let (de_impl_generics, _type_generics, _where_clause) = de_generics.split_for_impl();
+	let de_where_clause = make_where_clause(context, item, &de_lifetime);
+
+	let tag_enum = make_tag_enum(item);
+	let enum_info = make_enum_info(context, item, tag_field_name);
+
+	let internal = &context.internal;
+	let serde = &context.serde;
+
+	Some(quote! {
+		#[automatically_derived]
+		impl #impl_generics #enum_name #type_generics #where_clause {
+			/// Deserialize the content of a specific variant, ignoring the tag field.
+			///
+			/// Only the content field of the given variant is used.
+			/// The tag field, unknown fields and the content fields of other variants are ignored.
+			#vis fn deserialize_as<#de_lifetime, D: #serde::Deserializer<#de_lifetime>>(
+				deserializer: D,
+				tag: #tag_enum_name,
+			) -> ::core::result::Result<Self, D::Error>
+			#de_where_clause
+			{
+				struct Visitor #type_generics {
+					tag: #tag_enum_name,
+					_phantom: ::core::marker::PhantomData<fn() -> #enum_name #type_generics>,
+				};
+				impl #de_impl_generics #serde::de::Visitor<#de_lifetime> for Visitor #type_generics #de_where_clause {
+					type Value = #enum_name #type_generics;
+
+					fn expecting(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+						f.write_str("map with data fields")
+					}
+
+					fn visit_map<A: #serde::de::MapAccess<#de_lifetime>>(self, map: A) -> ::core::result::Result<Self::Value, A::Error> {
+						#tag_enum
+						#enum_info
+
+						let remaining = #internal::ignore_tag(&ENUM_INFO, map);
+						match self.tag {
+							#(#match_arms)*
+						}
+					}
+				}
+
+				deserializer.deserialize_map(Visitor {
+					tag,
+					_phantom: ::core::marker::PhantomData
+				})
+			}
+		}
+	})
+}
+
+/// Generate the block that deserializes the content of a variant from the `RemainingFields` in `remaining`.
+///
+/// For the `#[serde(catch_all)]` variant, the captured tag must be available as `field_tag`.
+fn make_variant_body(
+	context: &mut Context,
+	item: &crate::input::Enum,
+	variant: &crate::input::Variant,
+	tag_field_name: &str,
+) -> TokenStream {
+	let variant_name = &variant.ident;
+
+	// The `#[serde(other)]` variant ignores all content.
+	if variant.attr.other.is_some() {
+		let internal = &context.internal;
+		return quote! {{
+			#internal::deserialize_variant_other(remaining)?;
+			Ok(Self::Value::#variant_name)
+		}};
+	}
+
+	// The `#[serde(catch_all)]` variant captures the tag and the content stored under the tag value.
+	if let (Some(_), Some(catch_all_fields)) = (&variant.attr.catch_all, variant.catch_all_fields()) {
+		let internal = &context.internal;
+		let fields = super::catch_all_expression(variant);
+		let content_template = super::content_template(item);
+		let tag_type = catch_all_fields.tag;
+		return quote! {{
+			let field_tag: #tag_type = field_tag.deserialize_into()?;
+			let tag = ::core::convert::AsRef::<::core::primitive::str>::as_ref(&field_tag);
+			let content_field_name = #internal::content_field_name(#content_template, tag);
+			let field_content = #internal::deserialize_variant_catch_all(&content_field_name, remaining)?;
+			Ok(Self::Value::#variant_name #fields)
+		}};
+	}
+
+	let variant_content_name = super::variant_content_name(item, variant);
+	let variant_content_aliases = super::variant_content_aliases(item, variant);
+	let field_names = {
+		let internal = &context.internal;
+		quote! {
+			const FIELD_NAMES: #internal::FieldNames = #internal::FieldNames {
+				tag: #tag_field_name,
+				content: #variant_content_name,
+				content_aliases: &[#(#variant_content_aliases),*],
+			};
+		}
+	};
+
+	let fields = super::fields_expression(&variant.fields);
+
+	// Unit variants only accept the encoding set with `#[serde(unit_content = "...")]`.
+	// Without it, empty tuple and struct variants with `#[serde(empty_as_unit)]` also accept an empty sequence or map.
+	if super::is_unit_like(item, variant) {
+		let internal = &context.internal;
+		let encoding = match super::unit_content(item) {
+			None if !variant.fields.is_unit() => quote!(#internal::UnitContent::AnyEmpty),
+			None => quote!(#internal::UnitContent::Optional),
+			Some(UnitContentKind::Omit) => quote!(#internal::UnitContent::Omit),
+			Some(UnitContentKind::Null) => quote!(#internal::UnitContent::Null),
+			Some(UnitContentKind::Empty) => quote!(#internal::UnitContent::Empty),
+		};
+		return quote! {{
+			#field_names
+			#internal::deserialize_variant_unit(&FIELD_NAMES, remaining, #encoding)?;
+			Ok(Self::Value::#variant_name #fields)
+		}};
+	}
+
+	// With `#[serde(default)]`, a missing content field gives the default payload.
+	let data = make_data_struct(context, item, variant);
+	let internal = &context.internal;
+	let deserialize_variant = if super::has_default(item, variant) {
+		quote!(#internal::deserialize_variant_optional)
+	} else {
+		quote!(#internal::deserialize_variant_required)
+	};
+	quote! {{
+		#data
+		#field_names
+		let Data #fields = #deserialize_variant(&FIELD_NAMES, remaining)?;
+		Ok(Self::Value::#variant_name #fields)
+	}}
+}
+
 fn make_where_clause(
 	context: &Context,
 	item: &crate::input::Enum,
@@ -100,7 +269,21 @@ fn make_where_clause(
 
 	let mut predicates = Vec::<syn::WherePredicate>::new();
 	for variant in &item.variants {
-		for ty in variant.fields.iter_types() {
+		for (attrs, ty) in variant.fields.iter_fields() {
+			// Skipped fields are filled with their default value instead.
+			// Unless a default function is given, the type parameters used by the field must implement `Default`.
+			if attrs.skips_deserializing() {
+				if attrs.default_path.is_none() {
+					let generics = util::prune_generics(&item.generics, std::iter::once(ty));
+					for param in generics.type_params() {
+						let ident = &param.ident;
+						predicates.push(syn::parse_quote! {
+							#ident: ::core::default::Default
+						})
+					}
+				}
+				continue;
+			}
 			let ty = util::strip_type_wrappers(ty);
 			if util::type_uses_generic(ty, &item.generics) {
 				predicates.push(syn::parse_quote! {
@@ -108,6 +291,17 @@ fn make_where_clause(
 				})
 			}
 		}
+
+		// The derived `Default` implementation of the `Data` struct requires all type parameters to implement `Default`.
+		if super::has_default(item, variant) {
+			let generics = util::prune_generics(&item.generics, variant.fields.iter_types());
+			for param in generics.type_params() {
+				let ident = &param.ident;
+				predicates.push(syn::parse_quote! {
+					#ident: ::core::default::Default
+				})
+			}
+		}
 	}
 
 	match &item.generics.where_clause {
@@ -126,25 +320,157 @@ fn make_where_clause(
 	}
 }
 
-fn make_tag_enum(context: &Context, item: &crate::input::Enum) -> TokenStream {
-	let variant_name: Vec<_> = item.variants.iter().map(|x| &x.ident).collect();
-	let rename_all = &item.attr.rename_all;
-	let rename = super::tag_struct_name(item);
-
-	let serde = &context.serde;
-	let serde_str = serde.to_token_stream().to_string();
+/// Generate the `Tag` enum with a variant for each known variant of the enum.
+fn make_tag_enum(item: &crate::input::Enum) -> TokenStream {
+	// The `#[serde(catch_all)]` variant has no known tag value.
+	let variants = item
+		.variants
+		.iter()
+		.filter(|variant| variant.attr.catch_all.is_none())
+		.map(|variant| &variant.ident);
 
 	quote! {
-		#[derive(#serde::Deserialize)]
-		#[serde(crate = #serde_str)]
-		#[serde(rename = #rename)]
-		#rename_all
+		#[derive(::core::clone::Clone, ::core::marker::Copy, ::core::cmp::PartialEq)]
 		enum Tag {
-			#(#variant_name,)*
+			#(#variants,)*
+		}
+	}
+}
+
+/// Generate the `ENUM_INFO` constant with the tag values and content field names of all variants.
+fn make_enum_info(context: &Context, item: &crate::input::Enum, tag_field_name: &str) -> TokenStream {
+	let internal = &context.internal;
+	let mut tag_values = Vec::with_capacity(item.variants.len());
+	let mut content_fields = Vec::with_capacity(item.variants.len());
+	for variant in &item.variants {
+		if variant.attr.catch_all.is_some() {
+			continue;
+		}
+		let variant_name = &variant.ident;
+		for value in super::variant_tag_values(item, variant) {
+			let value = value.to_expression(internal);
+			tag_values.push(quote!((#value, Tag::#variant_name)));
+		}
+
+		for name in super::variant_content_names(item, variant) {
+			content_fields.push(quote!((#name, Tag::#variant_name)));
+		}
+	}
+
+	let unknown_tag = if let Some(variant) = item.other_variant() {
+		let variant_name = &variant.ident;
+		quote!(#internal::UnknownTag::Variant(Tag::#variant_name))
+	} else if item.catch_all_variant().is_some() {
+		quote!(#internal::UnknownTag::Capture)
+	} else {
+		quote!(#internal::UnknownTag::Error)
+	};
+	let buffer_unknown_fields = item.catch_all_variant().is_some();
+	let infer_tag = item.attr.infer_tag.is_some();
+	let deny_unknown_fields = item.attr.deny_unknown_fields.is_some();
+	let deny_inactive_content = super::deny_inactive_content(item);
+	let null_inactive = item.attr.null_inactive.is_some();
+
+	quote! {
+		const ENUM_INFO: #internal::EnumInfo<Tag> = #internal::EnumInfo {
+			tag_field_name: #tag_field_name,
+			tag_values: &[#(#tag_values),*],
+			unknown_tag: #unknown_tag,
+			content_fields: &[#(#content_fields),*],
+			buffer_unknown_fields: #buffer_unknown_fields,
+			infer_tag: #infer_tag,
+			deny_unknown_fields: #deny_unknown_fields,
+			deny_inactive_content: #deny_inactive_content,
+			null_inactive: #null_inactive,
+		};
+	}
+}
+
+/// Generate the implementation of the `ContentFields` trait used by `Preserving<T>`.
+fn make_content_fields_impl(context: &Context, item: &crate::input::Enum) -> TokenStream {
+	let enum_name = &item.ident;
+	let mut content_fields = Vec::with_capacity(item.variants.len());
+	let mut match_arms = Vec::with_capacity(item.variants.len());
+	let mut null_arms = Vec::with_capacity(item.variants.len());
+	for (index, variant) in item.variants.iter().enumerate() {
+		let variant_name = &variant.ident;
+		for name in super::variant_content_names(item, variant) {
+			content_fields.push(quote!((#name, #index)));
+		}
+		let null_content_names = super::null_content_names(item, variant);
+		null_arms.push(quote!(Self::#variant_name { .. } => &[#(#null_content_names),*],));
+		if variant.attr.catch_all.is_some() {
+			match_arms.push(quote!(Self::#variant_name { .. } => ::core::option::Option::None,));
+		} else {
+			match_arms.push(quote!(Self::#variant_name { .. } => ::core::option::Option::Some(#index),));
+		}
+	}
+
+	let (impl_generics, type_generics, where_clause) = item.generics.split_for_impl();
+	let internal = &context.internal;
+	quote! {
+		#[automatically_derived]
+		impl #impl_generics #internal::ContentFields for #enum_name #type_generics #where_clause {
+			const CONTENT_FIELDS: &'static [(&'static ::core::primitive::str, ::core::primitive::usize)] = &[#(#content_fields),*];
+
+			fn variant_index(&self) -> ::core::option::Option<::core::primitive::usize> {
+				match self {
+					#(#match_arms)*
+				}
+			}
+
+			fn null_content_fields(&self) -> &'static [&'static ::core::primitive::str] {
+				match self {
+					#(#null_arms)*
+				}
+			}
 		}
 	}
 }
 
+/// Generate the `validate` closure that checks the content of inactive variants.
+///
+/// Returns `None` if the enum does not have `#[serde(validate_inactive)]`.
+fn make_validate(context: &mut Context, item: &crate::input::Enum, de_lifetime: &syn::Lifetime) -> Option<TokenStream> {
+	item.attr.validate_inactive.as_ref()?;
+
+	let mut arms = Vec::with_capacity(item.variants.len());
+	for variant in &item.variants {
+		if variant.attr.catch_all.is_some() {
+			continue;
+		}
+		let variant_name = &variant.ident;
+
+		// The `#[serde(other)]` variant has no content.
+		if variant.attr.other.is_some() {
+			arms.push(quote!(Tag::#variant_name => ::core::result::Result::Ok(()),));
+			continue;
+		}
+
+		let data = make_data_struct(context, item, variant);
+		let generics = util::prune_generics(&item.generics, variant.fields.iter_types());
+		let (_impl_generics, type_generics, _where_clause) = generics.split_for_impl();
+		// The fields of the `Data` struct are never read, since it is only used for validation.
+		arms.push(quote! {
+			Tag::#variant_name => {
+				#[allow(dead_code)]
+				#data
+				content.deserialize_into::<Data #type_generics, A::Error>()?;
+				::core::result::Result::Ok(())
+			},
+		});
+	}
+
+	let internal = &context.internal;
+	Some(quote! {
+		let validate = |tag: Tag, content: #internal::Content<#de_lifetime>| -> ::core::result::Result<(), A::Error> {
+			match tag {
+				#(#arms)*
+			}
+		};
+	})
+}
+
 fn make_data_struct(context: &mut Context, item: &crate::input::Enum, variant: &crate::input::Variant) -> TokenStream {
 	// Remove generic parameters not needed for the fields of this variant.
 	let fields = &variant.fields;
@@ -155,29 +481,23 @@ fn make_data_struct(context: &mut Context, item: &crate::input::Enum, variant: &
 	let data_rename_all = variant.rename_all_rule(item);
 	let data_rename = &variant.attr.rename;
 	let data_deny_unknown_fields = &item.attr.deny_unknown_fields;
+	let data_default = super::has_default(item, variant).then(|| {
+		quote! {
+			#[derive(::core::default::Default)]
+			#[serde(default)]
+		}
+	});
 
 	let serde = &context.serde;
 	let serde_str = serde.to_token_stream().to_string();
 
-	let mut tokens = quote! {
+	quote! {
 		#[derive(#serde::Deserialize)]
 		#[serde(crate = #serde_str)]
 		#data_rename_all
 		#data_rename
 		#data_deny_unknown_fields
+		#data_default
 		struct Data #impl_generics #where_clause #fields;
-	};
-
-	// If this is a unit variant, add a `Default` implementation for the data struct.
-	if fields.is_unit() {
-		tokens.extend(quote! {
-			impl ::core::default::Default for Data {
-				fn default() -> Self {
-					Self
-				}
-			}
-		})
 	}
-
-	tokens
 }
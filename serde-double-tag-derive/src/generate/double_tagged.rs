@@ -0,0 +1,74 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::Context;
+
+/// Generate the implementation of `DoubleTagged` for the enum.
+///
+/// Enums with a `#[serde(catch_all)]` variant can not implement `DoubleTagged`,
+/// since the tag value of that variant is not known at compile time.
+pub fn impl_double_tagged(context: &mut Context, item: crate::input::Enum) -> TokenStream {
+	if let Some(variant) = item.catch_all_variant() {
+		context.spanned_error(
+			&variant.ident,
+			"#[derive(DoubleTagged)] does not support #[serde(catch_all)] variants",
+		);
+		return TokenStream::new();
+	}
+
+	let tag_field_name = super::tag_field_name(context, &item);
+	let enum_name = &item.ident;
+	let internal = &context.internal;
+	let mut variants = Vec::with_capacity(item.variants.len());
+	let mut tag_value_arms = Vec::with_capacity(item.variants.len());
+	let mut content_key_arms = Vec::with_capacity(item.variants.len());
+	for variant in &item.variants {
+		let variant_name = &variant.ident;
+		let name = variant_name.to_string();
+		let tag_value = super::variant_tag_value(&item, variant).to_string();
+		let has_content = !super::is_unit_like(&item, variant);
+		let writes_content = super::writes_content(&item, variant);
+		let content_key = match variant.attr.other {
+			Some(_) => quote!(::core::option::Option::None),
+			None => {
+				let content_key = super::variant_content_name(&item, variant);
+				quote!(::core::option::Option::Some(#content_key))
+			},
+		};
+		variants.push(quote! {
+			#internal::VariantInfo {
+				name: #name,
+				tag_value: #tag_value,
+				content_key: #content_key,
+				has_content: #has_content,
+			}
+		});
+		tag_value_arms.push(quote!(Self::#variant_name { .. } => #tag_value,));
+		if writes_content {
+			content_key_arms.push(quote!(Self::#variant_name { .. } => #content_key,));
+		} else {
+			content_key_arms.push(quote!(Self::#variant_name { .. } => ::core::option::Option::None,));
+		}
+	}
+
+	let (impl_generics, type_generics, where_clause) = item.generics.split_for_impl();
+	quote! {
+		#[automatically_derived]
+		impl #impl_generics #internal::DoubleTagged for #enum_name #type_generics #where_clause {
+			const TAG_FIELD: &'static ::core::primitive::str = #tag_field_name;
+			const VARIANTS: &'static [#internal::VariantInfo] = &[#(#variants),*];
+
+			fn tag_value(&self) -> &'static ::core::primitive::str {
+				match self {
+					#(#tag_value_arms)*
+				}
+			}
+
+			fn content_key(&self) -> ::core::option::Option<&'static ::core::primitive::str> {
+				match self {
+					#(#content_key_arms)*
+				}
+			}
+		}
+	}
+}
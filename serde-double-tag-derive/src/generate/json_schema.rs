@@ -1,6 +1,7 @@
 use proc_macro2::TokenStream;
 use quote::quote;
 
+use crate::input::attributes::UnitContentKind;
 use crate::{util, Context};
 
 /// Generate code that implement the serde `Deserialize` trait for an enum using the double-tag format.
@@ -11,11 +12,21 @@ pub fn impl_json_schema(context: &mut Context, item: crate::input::Enum) -> Toke
 	let tag_values: Vec<_> = item
 		.variants
 		.iter()
-		.map(|variant| super::variant_tag_value(&item, variant))
+		.filter(|variant| variant.attr.catch_all.is_none())
+		.flat_map(|variant| super::variant_tag_values(&item, variant))
 		.collect();
-	let variant_count = item.variants.len();
-	let tag_schema = make_tag_schema(context, &tag_values);
-	let subschemas = make_variant_subschemas(context, &item, &tag_field_name, &tag_values);
+	let open_tag = item.other_variant().is_some() || item.catch_all_variant().is_some();
+	let tag_schema = make_tag_schema(context, &tag_values, open_tag);
+	let subschemas = make_variant_subschemas(context, &item, &tag_field_name);
+
+	// With `#[serde(infer_tag)]`, the tag field may be omitted.
+	let require_tag = match item.attr.infer_tag {
+		Some(_) => None,
+		None => {
+			let internal = &context.internal;
+			Some(quote!(required.insert(#internal::string(#tag_field_name));))
+		},
+	};
 
 	let internal = &context.internal;
 	let schemars = &context.schemars;
@@ -35,15 +46,12 @@ pub fn impl_json_schema(context: &mut Context, item: crate::input::Enum) -> Toke
 			}
 
 			fn json_schema(generator: &mut #schemars::gen::SchemaGenerator) -> #schemars::schema::Schema {
-				let mut type_values = ::std::vec::Vec::with_capacity(#variant_count);
-				#(
-					type_values.push(#internal::string(#tag_values));
-				)*
 				let mut properties = #schemars::Map::with_capacity(1);
+				#[allow(unused_mut)]
 				let mut required = ::std::collections::BTreeSet::new();
 
 				properties.insert(#internal::string(#tag_field_name), #tag_schema);
-				required.insert(#internal::string(#tag_field_name));
+				#require_tag
 
 				#schemars::schema::Schema::Object(
 					#schemars::schema::SchemaObject {
@@ -66,36 +74,23 @@ pub fn impl_json_schema(context: &mut Context, item: crate::input::Enum) -> Toke
 					}
 				)
 			}
+
+			fn _schemars_private_non_optional_json_schema(generator: &mut #schemars::gen::SchemaGenerator) -> #schemars::schema::Schema {
+				#internal::flatten_schema(<Self as #schemars::JsonSchema>::json_schema(generator))
+			}
 		}
 	}
 }
 
 /// Generate code that creates a `schemars::schema::Schema` for an enum tag.
-fn make_tag_schema(context: &Context, tag_values: &[String]) -> TokenStream {
-	let count = tag_values.len();
+///
+/// If the enum has a `#[serde(other)]` or `#[serde(catch_all)]` variant, any value of the same type as the known tag values is accepted.
+fn make_tag_schema(context: &Context, tag_values: &[util::TagValue], open: bool) -> TokenStream {
 	let internal = &context.internal;
-	let schemars = &context.schemars;
-	quote! {{
-		#[allow(unused_mut)]
-		let mut tag_values = ::std::vec::Vec::with_capacity(#count);
-		#(
-			tag_values.push(#internal::json_value(#tag_values));
-		)*
-
-		#schemars::schema::Schema::Object(
-			#schemars::schema::SchemaObject {
-				instance_type: ::core::option::Option::Some(
-					#schemars::schema::SingleOrVec::Single(
-						::std::boxed::Box::new(
-							#schemars::schema::InstanceType::String
-						)
-					)
-				),
-				enum_values: ::core::option::Option::Some(tag_values),
-				.. ::core::default::Default::default()
-			}
-		)
-	}}
+	let tag_values = tag_values.iter().map(|value| value.to_expression(internal));
+	quote! {
+		#internal::enum_value(&[#(#tag_values),*], #open)
+	}
 }
 
 /// Generate code that returns a `schemars::schema::Schema` for the given [`Fields`].
@@ -104,40 +99,100 @@ fn make_schema_for_fields(
 	item: &crate::input::Enum,
 	variant: &crate::input::Variant,
 ) -> TokenStream {
+	let unit_like = super::is_unit_like(item, variant);
 	match &variant.fields {
-		crate::input::Fields::Unit => make_schema_for_unit_value(context),
-		crate::input::Fields::Tuple(fields) => make_schema_for_tuple_fields(context, fields),
-		crate::input::Fields::Struct(fields) => make_schema_for_struct_fields(context, item, variant, fields),
+		crate::input::Fields::Tuple(fields) if !unit_like => {
+			make_schema_for_tuple_fields(context, item, variant, fields)
+		},
+		crate::input::Fields::Struct(fields) if !unit_like => {
+			make_schema_for_struct_fields(context, item, variant, fields)
+		},
+		_ => make_schema_for_unit_value(context, item),
 	}
 }
 
 /// Generate code that returns a `schemars::schema::Schema` for a unit value.
-fn make_schema_for_unit_value(context: &Context) -> TokenStream {
+///
+/// With `#[serde(unit_content = "empty")]`, this is an empty map.
+fn make_schema_for_unit_value(context: &Context, item: &crate::input::Enum) -> TokenStream {
 	let internal = &context.internal;
-	quote!(#internal::unit_schema())
+	match super::unit_content(item) {
+		Some(UnitContentKind::Empty) => quote!(#internal::empty_map_schema()),
+		_ => quote!(#internal::unit_schema()),
+	}
 }
 
 /// Generate code that returns the `schemars::schema::SubschemaValidation` for all variants of an enum (wrapped in `Option<Box<T>>`).
 ///
 /// The subschemas are a bunch of `if/then` schemas that match on the enum tag,
 /// and extend the object with a required property for the variant.
-fn make_variant_subschemas(
-	context: &mut Context,
-	item: &crate::input::Enum,
-	tag_field_name: &str,
-	tag_values: &[String],
-) -> TokenStream {
+fn make_variant_subschemas(context: &mut Context, item: &crate::input::Enum, tag_field_name: &str) -> TokenStream {
 	let deny_unknown_fields = item.attr.deny_unknown_fields.is_some();
+	let deny_inactive_content = super::deny_inactive_content(item);
+	let null_inactive = item.attr.null_inactive.is_some();
 
 	// Generate the code for the subschema validation for each variant.
+	// The `#[serde(catch_all)]` variant does not have a fixed tag value, so it gets no subschema.
+	// Unit variants only get a subschema with `#[serde(unit_content = "...")]`.
 	let mut subschemas = Vec::with_capacity(item.variants.len());
-	for (variant, tag_value) in item.variants.iter().zip(tag_values) {
-		if variant.fields.is_unit() {
+	for variant in &item.variants {
+		if variant.attr.catch_all.is_some() || variant.attr.other.is_some() {
+			continue;
+		}
+		let omitted = super::is_unit_like(item, variant) && !super::writes_content(item, variant);
+		if omitted && super::unit_content(item).is_none() {
+			continue;
+		}
+		let internal = &context.internal;
+		let tag_value = super::variant_tag_value(item, variant).to_expression(internal);
+		let tag_aliases: Vec<_> = super::variant_aliases(variant)
+			.into_iter()
+			.map(|alias| util::TagValue::Str(alias).to_expression(internal))
+			.collect();
+		let content_name = super::variant_content_name(item, variant);
+		let content_aliases = super::variant_content_aliases(item, variant);
+		let inactive_content: Vec<_> = item
+			.variants
+			.iter()
+			.filter(|other| other.ident != variant.ident)
+			.flat_map(|other| super::variant_content_names(item, other))
+			.filter(|name| *name != content_name && !content_aliases.contains(name))
+			.collect();
+		let other_fields = quote! {
+			#internal::OtherFields {
+				inactive_content: &[#(#inactive_content),*],
+				deny_unknown_fields: #deny_unknown_fields,
+				deny_inactive_content: #deny_inactive_content,
+				null_inactive: #null_inactive,
+			}
+		};
+		if omitted {
+			subschemas.push(quote! {
+				#internal::omitted_variant_subschema(
+					#tag_field_name,
+					#tag_value,
+					&[#(#tag_aliases),*],
+					&[#content_name, #(#content_aliases),*],
+					#other_fields,
+				)
+			});
 			continue;
 		}
 		let fields_schema = make_schema_for_fields(context, item, variant);
+		let content_required = !super::has_default(item, variant);
 		let internal = &context.internal;
-		subschemas.push(quote!(#internal::variant_subschema(#tag_field_name, #tag_value, #fields_schema, #deny_unknown_fields)));
+		subschemas.push(quote! {
+			#internal::variant_subschema(
+				#tag_field_name,
+				#tag_value,
+				&[#(#tag_aliases),*],
+				#content_name,
+				&[#(#content_aliases),*],
+				#fields_schema,
+				#content_required,
+				#other_fields,
+			)
+		});
 	}
 
 	// Combine the subschemas into a single `Option<SubschemaValidation>` object.
@@ -176,27 +231,54 @@ fn make_schema_for_struct_fields(
 	variant: &crate::input::Variant,
 	fields: &crate::input::StructFields,
 ) -> TokenStream {
-	let field_name: Vec<_> = fields
+	// Skipped fields are left out of the schema.
+	let fields: Vec<_> = fields
 		.fields
+		.iter()
+		.filter(|field| !field.attrs.skips_serializing() && !field.attrs.skips_deserializing())
+		.collect();
+	let field_name: Vec<_> = fields
 		.iter()
 		.map(|field| super::field_name(item, variant, field))
 		.collect();
-	let field_type: Vec<_> = fields.fields.iter().map(|x| &x.ty).collect();
-	let field_count = fields.fields.len();
+	let field_count = fields.len();
+	let variant_default = super::has_default(item, variant);
 
 	let schemars = &context.schemars;
 	let internal = &context.internal;
+	let insert_fields = fields.iter().zip(&field_name).map(|(field, field_name)| {
+		let field_schema = make_schema_for_field(context, &field.attrs, &field.ty);
+		let insert = quote! {
+			properties.insert(#internal::string(#field_name), #field_schema);
+		};
+		// Fields with a default value are never required.
+		// With `#[serde(default)]` on the variant, missing fields are taken from the default payload.
+		// Fields with `#[serde(skip_serializing_if = "...")]` may be left out when serializing.
+		if field.attrs.default_expression().is_some() || variant_default || field.attrs.skip_serializing_if.is_some() {
+			return insert;
+		}
+		match &field.attrs.schema_with {
+			Some(_) => quote! {
+				#insert
+				required.insert(#internal::string(#field_name));
+			},
+			None => {
+				let schema_type = field.attrs.schema_type.as_ref().map_or(&field.ty, |x| &x.value.ty);
+				quote! {
+					#insert
+					if <#schema_type as #schemars::JsonSchema>::_schemars_private_is_option() == false {
+						required.insert(#internal::string(#field_name));
+					}
+				}
+			},
+		}
+	});
 	quote! {{
 		#[allow(unused_mut)]
 		let mut properties = #schemars::Map::with_capacity(#field_count);
 		#[allow(unused_mut)]
 		let mut required = ::std::collections::BTreeSet::new();
-		#(
-			properties.insert(#internal::string(#field_name), generator.subschema_for::<#field_type>());
-			if <#field_type as #schemars::JsonSchema>::_schemars_private_is_option() == false {
-				required.insert(#internal::string(#field_name));
-			}
-		)*
+		#(#insert_fields)*
 		#schemars::schema::Schema::Object(
 			#schemars::schema::SchemaObject {
 				instance_type: ::core::option::Option::Some(
@@ -219,32 +301,92 @@ fn make_schema_for_struct_fields(
 	}}
 }
 
+/// Generate code that returns a `schemars::schema::Schema` for a single field.
+///
+/// The schema can be overridden with `#[schemars(with = "...")]` or `#[schemars(schema_with = "...")]`.
+/// The default value of the field is added to the schema, unless the field is serialized with a custom function.
+fn make_schema_for_field(
+	context: &Context,
+	attrs: &crate::input::attributes::FieldAttributes,
+	ty: &syn::Type,
+) -> TokenStream {
+	let schemars = &context.schemars;
+	let internal = &context.internal;
+	let schema = match (&attrs.schema_with, &attrs.schema_type) {
+		(Some(schema_with), _) => {
+			let schema_with = &schema_with.value.path;
+			quote!(#schema_with(generator))
+		},
+		(None, Some(schema_type)) => {
+			let schema_type = &schema_type.value.ty;
+			quote!(generator.subschema_for::<#schema_type>())
+		},
+		(None, None) => quote!(generator.subschema_for::<#ty>()),
+	};
+
+	let custom_serialize = attrs.with.is_some() || attrs.serialize_with.is_some();
+	match attrs.default_expression() {
+		Some(default) if !custom_serialize => quote! {
+			#internal::with_default(#schema, {
+				let default: #ty = #default;
+				#schemars::_schemars_maybe_to_value!(default)
+			})
+		},
+		_ => schema,
+	}
+}
+
 /// Generate code that returns a `schemars::schema::Schema` for tuple fields.
-fn make_schema_for_tuple_fields(context: &mut Context, fields: &crate::input::TupleFields) -> TokenStream {
+fn make_schema_for_tuple_fields(
+	context: &mut Context,
+	item: &crate::input::Enum,
+	variant: &crate::input::Variant,
+	fields: &crate::input::TupleFields,
+) -> TokenStream {
 	match &fields.fields.len() {
 		// Treat single-field tuple variants as the inner type.
 		1 => {
-			let field_type = &fields.fields[0].ty;
-			quote! { generator.subschema_for::<#field_type>() }
+			let field = &fields.fields[0];
+			make_schema_for_field(context, &field.attrs, &field.ty)
 		},
 
 		// Treat the rest as fixed-size arrays.
 		field_count => {
-			let field_type = fields.fields.iter().map(|x| &x.ty);
-			let item_count = u32::try_from(*field_count)
-				.map_err(|_| {
-					context.spanned_error(
-						fields,
-						format_args!("too many fields in variant: {} > {}", fields.fields.len(), u32::MAX),
-					)
-				})
-				.unwrap_or(u32::MAX);
+			if u32::try_from(*field_count).is_err() {
+				context.spanned_error(
+					fields,
+					format_args!("too many fields in variant: {} > {}", fields.fields.len(), u32::MAX),
+				);
+			}
+
+			// Skipped fields are left out of the schema.
+			let fields: Vec<_> = fields
+				.fields
+				.iter()
+				.filter(|field| !field.attrs.skips_serializing() && !field.attrs.skips_deserializing())
+				.collect();
+			let field_count = fields.len();
+			let item_count = u32::try_from(field_count).unwrap_or(u32::MAX);
+
+			// Trailing fields with a default value may be left out.
+			// With `#[serde(default)]` on the variant, all fields may be left out.
+			let required_count = fields
+				.iter()
+				.rposition(|field| field.attrs.default_expression().is_none())
+				.filter(|_| !super::has_default(item, variant))
+				.map_or(0, |index| index + 1);
+			let min_items = u32::try_from(required_count).unwrap_or(u32::MAX);
 
 			let schemars = &context.schemars;
+			let push_items: Vec<_> = fields
+				.iter()
+				.map(|field| make_schema_for_field(context, &field.attrs, &field.ty))
+				.collect();
 			quote! {{
+				#[allow(unused_mut)]
 				let mut items = ::std::vec::Vec::with_capacity(#field_count);
 				#(
-					items.push(generator.subschema_for::<#field_type>());
+					items.push(#push_items);
 				)*
 				#schemars::schema::Schema::Object(
 					#schemars::schema::SchemaObject {
@@ -258,7 +400,7 @@ fn make_schema_for_tuple_fields(context: &mut Context, fields: &crate::input::Tu
 						array: ::core::option::Option::Some(::std::boxed::Box::new(
 							#schemars::schema::ArrayValidation {
 								items: ::core::option::Option::Some(#schemars::schema::SingleOrVec::Vec(items)),
-								min_items: ::core::option::Option::Some(#item_count),
+								min_items: ::core::option::Option::Some(#min_items),
 								max_items: ::core::option::Option::Some(#item_count),
 								..::core::default::Default::default()
 							}
@@ -276,7 +418,11 @@ fn make_where_clause(context: &Context, item: &crate::input::Enum) -> Option<syn
 
 	let mut predicates = Vec::<syn::WherePredicate>::new();
 	for variant in &item.variants {
-		for ty in variant.fields.iter_types() {
+		// Skipped fields are not part of the schema, so they do not need any bounds.
+		for (attrs, ty) in variant.fields.iter_fields() {
+			if attrs.skips_serializing() || attrs.skips_deserializing() {
+				continue;
+			}
 			let ty = util::strip_type_wrappers(ty);
 			if util::type_uses_generic(ty, &item.generics) {
 				predicates.push(syn::parse_quote! {
@@ -1,27 +1,31 @@
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{quote, ToTokens};
 
-use crate::Context;
+use crate::{util, Context};
+
+mod all_variants;
+pub use all_variants::impl_all_variants;
 
 mod deserialize;
 pub use deserialize::impl_deserialize_enum;
 
+mod double_tagged;
+pub use double_tagged::impl_double_tagged;
+
 mod serialize;
 pub use serialize::impl_serialize_enum;
 
+mod tag_enum;
+pub use tag_enum::impl_tag_enum;
+
+mod variant_structs;
+pub use variant_structs::impl_variant_structs;
+
 #[cfg(feature = "schemars")]
 mod json_schema;
 #[cfg(feature = "schemars")]
 pub use json_schema::impl_json_schema;
 
-/// Compute the name of the tag enum.
-fn tag_struct_name(item: &crate::input::Enum) -> String {
-	match &item.attr.rename {
-		Some(x) => format!("{}Tag", x.value.value()),
-		None => format!("{}Tag", item.ident),
-	}
-}
-
 /// Compute the name of the tag field for an enum.
 fn tag_field_name(context: &mut Context, item: &crate::input::Enum) -> String {
 	match &item.attr.tag {
@@ -33,8 +37,132 @@ fn tag_field_name(context: &mut Context, item: &crate::input::Enum) -> String {
 	}
 }
 
+/// Parse the name of a generated item from an attribute value.
+///
+/// Reports an error and returns `None` if the value is not a valid identifier.
+fn item_name<K>(
+	context: &mut Context,
+	name: &crate::input::attributes::KeyValueArg<K, syn::LitStr>,
+) -> Option<syn::Ident> {
+	match name.value.parse() {
+		Ok(ident) => Some(ident),
+		Err(_) => {
+			context.spanned_error(&name.value, "expected a valid identifier");
+			None
+		},
+	}
+}
+
+/// Get the name of the public tag enum set with `#[serde(tag_enum = "...")]`, if any.
+fn tag_enum_name(context: &mut Context, item: &crate::input::Enum) -> Option<syn::Ident> {
+	item_name(context, item.attr.tag_enum.as_ref()?)
+}
+
+/// Check if content fields of inactive variants should be rejected.
+///
+/// This follows `#[serde(deny_unknown_fields)]`, unless overridden with `#[serde(inactive_content = "...")]`.
+fn deny_inactive_content(item: &crate::input::Enum) -> bool {
+	match &item.attr.inactive_content {
+		Some(inactive_content) => inactive_content.value.deny,
+		None => item.attr.deny_unknown_fields.is_some(),
+	}
+}
+
+/// Get the encoding of the content of unit variants set with `#[serde(unit_content = "...")]`, if any.
+fn unit_content(item: &crate::input::Enum) -> Option<crate::input::attributes::UnitContentKind> {
+	item.attr.unit_content.as_ref().map(|x| x.value.kind)
+}
+
+/// Check if a variant is encoded like a unit variant.
+///
+/// This is true for unit variants,
+/// and for tuple and struct variants without fields if the enum has `#[serde(empty_as_unit)]`.
+fn is_unit_like(item: &crate::input::Enum, variant: &crate::input::Variant) -> bool {
+	match &variant.fields {
+		crate::input::Fields::Unit => true,
+		_ if item.attr.empty_as_unit.is_none() => false,
+		fields => fields.iter_types().next().is_none(),
+	}
+}
+
+/// Check if missing content of a variant is taken from the `Default` implementation of the payload.
+///
+/// This is true for tuple and struct variants with `#[serde(default)]` on the variant or on the enum.
+fn has_default(item: &crate::input::Enum, variant: &crate::input::Variant) -> bool {
+	if variant.attr.catch_all.is_some() || is_unit_like(item, variant) {
+		return false;
+	}
+	variant.attr.default.is_some() || item.attr.default.is_some()
+}
+
+/// Check if a content field is written for a variant.
+///
+/// Unit variants only have a content field with `#[serde(unit_content = "null")]` or `#[serde(unit_content = "empty")]`.
+/// The `#[serde(other)]` variant never has a content field.
+fn writes_content(item: &crate::input::Enum, variant: &crate::input::Variant) -> bool {
+	use crate::input::attributes::UnitContentKind;
+	match unit_content(item) {
+		_ if variant.attr.other.is_some() => false,
+		_ if !is_unit_like(item, variant) => true,
+		Some(UnitContentKind::Null | UnitContentKind::Empty) => true,
+		Some(UnitContentKind::Omit) | None => false,
+	}
+}
+
+/// Compute the content field names that are serialized as `null` when a variant is active.
+///
+/// With `#[serde(null_inactive)]`, these are the content field names of all other variants.
+/// Without it, there are none.
+fn null_content_names(item: &crate::input::Enum, active: &crate::input::Variant) -> Vec<String> {
+	if item.attr.null_inactive.is_none() {
+		return Vec::new();
+	}
+	let active_names = variant_content_names(item, active);
+	item.variants
+		.iter()
+		.filter(|variant| variant.ident != active.ident)
+		.filter_map(|variant| variant_content_names(item, variant).into_iter().next())
+		.filter(|name| !active_names.contains(name))
+		.collect()
+}
+
 /// Compute the tag value for a variant.
-fn variant_tag_value(item: &crate::input::Enum, variant: &crate::input::Variant) -> String {
+///
+/// In order of precedence, this is:
+/// * the value of `#[serde(tag_value = ...)]` on the variant,
+/// * the discriminant of the variant if the enum has `#[serde(discriminant_as_tag)]`,
+/// * the serialized name of the variant.
+fn variant_tag_value(item: &crate::input::Enum, variant: &crate::input::Variant) -> util::TagValue {
+	if let Some(tag_value) = &variant.attr.tag_value {
+		tag_value.value.value.clone()
+	} else if let Some(discriminant) = item.discriminant_tag(variant) {
+		discriminant
+	} else {
+		util::TagValue::Str(variant_name(item, variant))
+	}
+}
+
+/// Compute all accepted tag values for a variant: the tag value followed by the aliases.
+fn variant_tag_values(item: &crate::input::Enum, variant: &crate::input::Variant) -> Vec<util::TagValue> {
+	let mut values = vec![variant_tag_value(item, variant)];
+	values.extend(variant_aliases(variant).into_iter().map(util::TagValue::Str));
+	values
+}
+
+/// Compute the tag value of a variant as string, used to derive the content field name.
+///
+/// For variants with a non-string tag value, this is the serialized name of the variant.
+fn variant_tag_string(item: &crate::input::Enum, variant: &crate::input::Variant) -> String {
+	match variant_tag_value(item, variant) {
+		util::TagValue::Str(value) => value,
+		_ => variant_name(item, variant),
+	}
+}
+
+/// Compute the serialized name of a variant.
+///
+/// This is the tag value, unless the tag value is overridden.
+fn variant_name(item: &crate::input::Enum, variant: &crate::input::Variant) -> String {
 	if let Some(name) = &variant.attr.rename {
 		name.value.value()
 	} else if let Some(rename_all) = &item.attr.rename_all {
@@ -44,6 +172,79 @@ fn variant_tag_value(item: &crate::input::Enum, variant: &crate::input::Variant)
 	}
 }
 
+/// Compute the name of the content field for a variant.
+///
+/// In order of precedence, this is:
+/// * the value of `#[serde(content = "...")]` on the variant,
+/// * the variant name with the `#[serde(rename_all_content = "...")]` rule of the enum applied,
+/// * the tag value substituted in the `#[serde(content = "...")]` template of the enum,
+/// * the tag value.
+///
+/// For variants with a non-string tag value, the variant name is used instead of the tag value (see [`variant_tag_string()`]).
+fn variant_content_name(item: &crate::input::Enum, variant: &crate::input::Variant) -> String {
+	if let Some(content) = &variant.attr.content {
+		content.value.value()
+	} else if let Some(rename_all_content) = &item.attr.rename_all_content {
+		rename_all_content
+			.value
+			.rule
+			.apply_to_variant(&variant.ident.to_string())
+	} else if let Some(template) = &item.attr.content {
+		apply_content_template(&template.value.value(), &variant_tag_string(item, variant))
+	} else {
+		variant_tag_string(item, variant)
+	}
+}
+
+/// Compute all accepted content field names for a variant: the content field name followed by the aliases.
+///
+/// The `#[serde(other)]` and `#[serde(catch_all)]` variants have no known content field names.
+fn variant_content_names(item: &crate::input::Enum, variant: &crate::input::Variant) -> Vec<String> {
+	if variant.attr.other.is_some() || variant.attr.catch_all.is_some() {
+		return Vec::new();
+	}
+	let mut names = vec![variant_content_name(item, variant)];
+	names.extend(variant_content_aliases(item, variant));
+	names
+}
+
+/// Generate an `Option<&str>` expression for the `#[serde(content = "...")]` template of the enum.
+fn content_template(item: &crate::input::Enum) -> TokenStream {
+	match &item.attr.content {
+		Some(template) => {
+			let template = &template.value;
+			quote!(::core::option::Option::Some(#template))
+		},
+		None => quote!(::core::option::Option::None),
+	}
+}
+
+/// Substitute the tag value in a content field name template.
+fn apply_content_template(template: &str, tag_value: &str) -> String {
+	template.replace("{tag}", tag_value)
+}
+
+/// Get the aliases for a variant.
+///
+/// Each alias is accepted as alternative tag value.
+fn variant_aliases(variant: &crate::input::Variant) -> Vec<String> {
+	variant.attr.alias.iter().map(|alias| alias.value.value()).collect()
+}
+
+/// Get the alternative content field names for a variant.
+///
+/// These are the aliases of the variant,
+/// substituted in the `#[serde(content = "...")]` template of the enum if there is one.
+fn variant_content_aliases(item: &crate::input::Enum, variant: &crate::input::Variant) -> Vec<String> {
+	match (&variant.attr.content, &item.attr.content) {
+		(None, Some(template)) => variant_aliases(variant)
+			.iter()
+			.map(|alias| apply_content_template(&template.value.value(), alias))
+			.collect(),
+		_ => variant_aliases(variant),
+	}
+}
+
 /// Compute the serialized name for a field.
 #[cfg_attr(not(feature = "schemars"), allow(unused))]
 fn field_name(item: &crate::input::Enum, variant: &crate::input::Variant, field: &crate::input::StructField) -> String {
@@ -84,3 +285,91 @@ fn fields_expression(fields: &crate::input::Fields) -> TokenStream {
 		},
 	}
 }
+
+/// Create an expression for capturing and specifying the fields of a `#[serde(catch_all)]` variant.
+///
+/// The returned tokens depend on the type of fields:
+/// * For tuple fields, this returns the tokens: `(field_tag, field_content)`.
+/// * For struct fields, this returns the tokens: `{ tag: field_tag, content: field_content }`.
+fn catch_all_expression(variant: &crate::input::Variant) -> TokenStream {
+	match &variant.fields {
+		crate::input::Fields::Struct(_) => quote! {
+			{ tag: field_tag, content: field_content }
+		},
+		_ => quote! {
+			(field_tag, field_content)
+		},
+	}
+}
+
+/// Generate the derive attributes for generated payload structs.
+///
+/// `JsonSchema` is only derived if the enum opts in with `#[serde(derive_json_schema)]`.
+fn payload_derives(context: &Context, item: &crate::input::Enum) -> TokenStream {
+	let serde = &context.serde;
+	let serde_str = serde.to_token_stream().to_string();
+	#[cfg(feature = "schemars")]
+	let schemars = match &item.attr.derive_json_schema {
+		Some(_) => {
+			let schemars = &context.schemars;
+			let schemars_str = schemars.to_token_stream().to_string();
+			quote! {
+				#[derive(#schemars::JsonSchema)]
+				#[schemars(crate = #schemars_str)]
+			}
+		},
+		None => TokenStream::new(),
+	};
+	#[cfg(not(feature = "schemars"))]
+	let schemars = {
+		let _ = item;
+		TokenStream::new()
+	};
+
+	quote! {
+		#[derive(#serde::Serialize, #serde::Deserialize)]
+		#[serde(crate = #serde_str)]
+		#schemars
+	}
+}
+
+/// Generate the payload struct for a variant.
+///
+/// The payload struct has the same fields as the variant, with the same serde attributes as the content field.
+fn payload_struct(
+	context: &Context,
+	item: &crate::input::Enum,
+	variant: &crate::input::Variant,
+	payload_name: &syn::Ident,
+) -> TokenStream {
+	let vis = &item.vis;
+	let fields = variant.fields.with_visibility(vis);
+	#[cfg(feature = "schemars")]
+	let fields = match &item.attr.derive_json_schema {
+		Some(_) => fields.with_schemars_attributes(),
+		None => fields,
+	};
+	let generics = util::prune_generics(&item.generics, fields.iter_types());
+	let (impl_generics, _type_generics, where_clause) = generics.split_for_impl();
+
+	let derives = payload_derives(context, item);
+	let rename_all = variant.rename_all_rule(item);
+	let rename = &variant.attr.rename;
+	let deny_unknown_fields = &item.attr.deny_unknown_fields;
+	let doc = format!(" The content of the [`{}::{}`] variant.", item.ident, variant.ident);
+
+	let definition = match fields {
+		crate::input::Fields::Struct(_) => quote!(#vis struct #payload_name #impl_generics #where_clause #fields),
+		_ => quote!(#vis struct #payload_name #impl_generics #fields #where_clause;),
+	};
+
+	quote! {
+		#[doc = #doc]
+		#[allow(missing_docs)]
+		#derives
+		#rename_all
+		#rename
+		#deny_unknown_fields
+		#definition
+	}
+}
@@ -1,6 +1,8 @@
 use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
+use syn::ext::IdentExt;
 
+use crate::input::attributes::{keyword, PathLit};
 use crate::{util, Context};
 
 /// Generate code that implement the serde `Serialize` trait for an enum using the double-tag format.
@@ -11,17 +13,25 @@ pub fn impl_serialize_enum(context: &mut Context, item: crate::input::Enum) -> T
 		.variants
 		.iter()
 		.map(|variant| {
+			if variant.attr.catch_all.is_some() {
+				return make_catch_all_arm(context, &item, variant);
+			}
+
 			let repr = make_repr_struct(context, &item, variant);
 			let variant_name = &variant.ident;
 			let fields = super::fields_expression(&variant.fields);
+			let data = make_data_expression(&item, variant);
+			let null_fields =
+				(0..super::null_content_names(&item, variant).len()).map(|i| quote::format_ident!("null_{}", i));
 			let serde = &context.serde;
 
 			quote! {
 				Self::#variant_name #fields => {
 					#repr
 					let repr = Repr {
-						tag: Tag::#variant_name,
-						data: Data #fields,
+						tag: TAG_VALUE,
+						data: #data,
+						#(#null_fields: (),)*
 					};
 					#serde::Serialize::serialize(&repr, serializer)
 				},
@@ -45,12 +55,49 @@ pub fn impl_serialize_enum(context: &mut Context, item: crate::input::Enum) -> T
 	}
 }
 
+/// Generate the match arm for a `#[serde(catch_all)]` variant.
+///
+/// The captured tag is written as tag value, and the content is written under the matching content field name.
+/// If the content is an `Option<T>`, the content field is omitted for `None`.
+/// With `#[serde(null_inactive)]`, the content fields of all known variants are written as `null`.
+fn make_catch_all_arm(
+	context: &mut Context,
+	item: &crate::input::Enum,
+	variant: &crate::input::Variant,
+) -> TokenStream {
+	let tag_field_name = super::tag_field_name(context, item);
+	let variant_name = &variant.ident;
+	let fields = super::catch_all_expression(variant);
+	let content = match variant.catch_all_fields() {
+		Some(fields) if util::is_option_type(fields.content) => {
+			quote!(::core::option::Option::as_ref(field_content))
+		},
+		_ => quote!(::core::option::Option::Some(field_content)),
+	};
+
+	let content_template = super::content_template(item);
+	let null_content_names = super::null_content_names(item, variant);
+
+	let internal = &context.internal;
+	quote! {
+		Self::#variant_name #fields => {
+			let tag = ::core::convert::AsRef::<::core::primitive::str>::as_ref(field_tag);
+			let content_field_name = #internal::content_field_name(#content_template, tag);
+			#internal::serialize_catch_all(serializer, #tag_field_name, tag, &content_field_name, #content, &[#(#null_content_names),*])
+		},
+	}
+}
+
 fn make_where_clause(context: &Context, item: &crate::input::Enum) -> Option<syn::WhereClause> {
 	let serde = &context.serde;
 
 	let mut predicates = Vec::<syn::WherePredicate>::new();
 	for variant in &item.variants {
-		for ty in variant.fields.iter_types() {
+		// Skipped fields are never serialized, so they do not need any bounds.
+		for (attrs, ty) in variant.fields.iter_fields() {
+			if attrs.skips_serializing() {
+				continue;
+			}
 			let ty = util::strip_type_wrappers(ty);
 			if util::type_uses_generic(ty, &item.generics) {
 				predicates.push(syn::parse_quote! {
@@ -79,14 +126,15 @@ fn make_where_clause(context: &Context, item: &crate::input::Enum) -> Option<syn
 fn make_repr_struct(context: &mut Context, item: &crate::input::Enum, variant: &crate::input::Variant) -> TokenStream {
 	// Make a struct with borrowed fields.
 	let (generics, lifetime) = util::add_lifetime(context, &item.generics, "serde_double_tag");
-	let borrowed_fields = variant.fields.add_lifetime(&lifetime);
+	let mut borrowed_fields = variant.fields.add_lifetime(&lifetime);
 
 	// Remove generic parameters not needed for the fields of this variant.
 	let generics = util::prune_generics(&generics, borrowed_fields.iter_types());
 	let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+	let field_functions = adapt_borrowed_fields(context, &mut borrowed_fields, &generics);
 
 	let tag_field_name = super::tag_field_name(context, item);
-	let data_field_name = super::variant_tag_value(item, variant);
+	let data_field_name = super::variant_content_name(item, variant);
 
 	// Prepare attributes for the `Repr` struct.
 	let repr_rename = item.attr.rename.clone().unwrap_or_else(|| {
@@ -95,27 +143,37 @@ fn make_repr_struct(context: &mut Context, item: &crate::input::Enum, variant: &
 			syn::LitStr::new(&item.ident.to_string(), item.ident.span()),
 		)
 	});
-	let data_field_skip = match variant.fields.is_unit() {
-		true => Some(quote!(#[serde(skip)])),
-		false => None,
+	let data_field_skip = match super::writes_content(item, variant) {
+		true => None,
+		false => Some(quote!(#[serde(skip)])),
 	};
 
-	// Prepare attributes for the `Tag` enum.
-	let tag_rename_all = &item.attr.rename_all;
-	let tag_rename = match &item.attr.rename {
-		None => format!("{}Tag", item.ident),
-		Some(x) => format!("{}Tag", x.value.value()),
-	};
-	let tag_rename = quote!(#[serde(rename = #tag_rename)]);
-	let variant_rename = &variant.attr.rename;
+	// With `#[serde(null_inactive)]`, the content fields of other variants are written as `null`.
+	let null_content_names = super::null_content_names(item, variant);
+	let null_content_idents = (0..null_content_names.len()).map(|i| quote::format_ident!("null_{}", i));
+
+	let internal = &context.internal;
+	let tag_value = super::variant_tag_value(item, variant).to_expression(internal);
 
 	// Prepare attributes for the `Data` struct.
+	// Unit variants with `#[serde(unit_content = "empty")]` are serialized as an empty map.
+	// Empty variants with `#[serde(empty_as_unit)]` are serialized like unit variants.
+	let data_fields = if is_empty_unit(item, variant) {
+		quote!({})
+	} else if super::is_unit_like(item, variant) {
+		TokenStream::new()
+	} else {
+		quote!(#borrowed_fields)
+	};
 	let data_rename_all = variant.rename_all_rule(item);
 	let data_rename = &variant.attr.rename;
 
 	let serde = &context.serde;
 	let serde_str = serde.to_token_stream().to_string();
-	let variant_name = &variant.ident;
+
+	// Bound the `data` field on the `Data` struct directly.
+	// Otherwise serde requires all type parameters to implement `Serialize`, including those of skipped fields.
+	let data_bound = quote!(Data #type_generics: #serde::Serialize).to_string();
 
 	quote! {
 		#[derive(#serde::Serialize)]
@@ -123,26 +181,127 @@ fn make_repr_struct(context: &mut Context, item: &crate::input::Enum, variant: &
 		#repr_rename
 		struct Repr #impl_generics #where_clause {
 			#[serde(rename = #tag_field_name)]
-			tag: Tag,
+			tag: #internal::TagValue,
 
 			#[serde(rename = #data_field_name)]
+			#[serde(bound = #data_bound)]
 			#data_field_skip
 			data: Data #type_generics,
-		}
 
-		#[derive(#serde::Serialize)]
-		#[serde(crate = #serde_str)]
-		#tag_rename_all
-		#tag_rename
-		enum Tag {
-			#variant_rename
-			#variant_name
+			#(
+				#[serde(rename = #null_content_names)]
+				#null_content_idents: (),
+			)*
 		}
 
+		const TAG_VALUE: #internal::TagValue = #tag_value;
+
+		// Skipped fields are never read.
+		#[allow(dead_code)]
 		#[derive(#serde::Serialize)]
 		#[serde(crate = #serde_str)]
 		#data_rename_all
 		#data_rename
-		struct Data #impl_generics #where_clause #borrowed_fields;
+		struct Data #impl_generics #where_clause #data_fields;
+
+		#field_functions
+	}
+}
+
+/// Adapt the field attributes that take a function for the borrowed fields of the `Data` struct.
+///
+/// The functions for `#[serde(skip_serializing_if = "...")]`, `#[serde(serialize_with = "...")]` and `#[serde(with = "...")]`
+/// take a reference to the field, but the fields of the `Data` struct are references themselves.
+/// They are replaced by associated functions of the `Data` struct that remove the extra reference.
+///
+/// Returns the `impl` block with the associated functions, if any field needs one.
+fn adapt_borrowed_fields(
+	context: &Context,
+	fields: &mut crate::input::Fields,
+	generics: &syn::Generics,
+) -> Option<TokenStream> {
+	let fields: Vec<_> = match fields {
+		crate::input::Fields::Unit => Vec::new(),
+		crate::input::Fields::Tuple(fields) => fields
+			.fields
+			.iter_mut()
+			.map(|field| (field.index.to_string(), &mut field.attrs, &field.ty))
+			.collect(),
+		crate::input::Fields::Struct(fields) => fields
+			.fields
+			.iter_mut()
+			.map(|field| (field.ident.unraw().to_string(), &mut field.attrs, &field.ty))
+			.collect(),
+	};
+
+	// Refer to the functions with explicit generic arguments, since serde may call them from a wrapper type.
+	let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+	let turbofish = type_generics.as_turbofish();
+	let function_path =
+		|function: &syn::Ident, span| PathLit::new(&quote!(Data #turbofish :: #function).to_string(), span);
+
+	let serde = &context.serde;
+	let mut functions = Vec::new();
+	for (name, attrs, ty) in fields {
+		if let Some(skip_serializing_if) = &mut attrs.skip_serializing_if {
+			let predicate = &skip_serializing_if.value.path;
+			let function = quote::format_ident!("skip_serializing_if_{}", name);
+			functions.push(quote! {
+				fn #function(value: &#ty) -> bool {
+					#predicate(*value)
+				}
+			});
+			skip_serializing_if.value = function_path(&function, skip_serializing_if.value.literal.span());
+		}
+
+		// The `Data` struct is only serialized, so `#[serde(with = "...")]` becomes `#[serde(serialize_with = "...")]`.
+		if let Some(with) = attrs.with.take() {
+			let module = &with.value.path;
+			let serialize = PathLit::new(&quote!(#module::serialize).to_string(), with.value.literal.span());
+			attrs.serialize_with = Some(
+				with.map_key(|key| keyword::serialize_with(key.span))
+					.map_value(|_| serialize),
+			);
+		}
+
+		if let Some(serialize_with) = &mut attrs.serialize_with {
+			let serialize = &serialize_with.value.path;
+			let function = quote::format_ident!("serialize_with_{}", name);
+			functions.push(quote! {
+				fn #function<S: #serde::Serializer>(value: &#ty, serializer: S) -> ::core::result::Result<S::Ok, S::Error> {
+					#serialize(*value, serializer)
+				}
+			});
+			serialize_with.value = function_path(&function, serialize_with.value.literal.span());
+		}
+	}
+
+	if functions.is_empty() {
+		return None;
+	}
+	Some(quote! {
+		#[automatically_derived]
+		impl #impl_generics Data #type_generics #where_clause {
+			#(#functions)*
+		}
+	})
+}
+
+/// Check if a variant is a unit variant that is serialized as an empty map.
+fn is_empty_unit(item: &crate::input::Enum, variant: &crate::input::Variant) -> bool {
+	super::is_unit_like(item, variant)
+		&& variant.attr.other.is_none()
+		&& super::unit_content(item) == Some(crate::input::attributes::UnitContentKind::Empty)
+}
+
+/// Generate the expression that constructs the `Data` struct from the captured fields of a variant.
+fn make_data_expression(item: &crate::input::Enum, variant: &crate::input::Variant) -> TokenStream {
+	if is_empty_unit(item, variant) {
+		quote!(Data {})
+	} else if super::is_unit_like(item, variant) {
+		quote!(Data)
+	} else {
+		let fields = super::fields_expression(&variant.fields);
+		quote!(Data #fields)
 	}
 }
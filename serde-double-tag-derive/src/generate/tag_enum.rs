@@ -0,0 +1,189 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::Context;
+
+/// Generate the public tag enum with a unit variant for each variant of the enum.
+///
+/// The name of the tag enum is set with `#[serde(tag_enum = "...")]`.
+/// This also generates a `tag()` function on the enum to get the tag of the active variant.
+pub fn impl_tag_enum(context: &mut Context, item: crate::input::Enum) -> TokenStream {
+	if item.attr.tag_enum.is_none() {
+		context.error(
+			item.ident.span(),
+			"missing required #[serde(tag_enum = \"...\")] attribute",
+		);
+		return TokenStream::new();
+	}
+	if let Some(variant) = item.catch_all_variant() {
+		context.spanned_error(
+			&variant.ident,
+			"#[derive(TagEnum)] does not support #[serde(catch_all)] variants",
+		);
+		return TokenStream::new();
+	}
+	let Some(tag_name) = super::tag_enum_name(context, &item) else {
+		return TokenStream::new();
+	};
+
+	let tag_enum = make_tag_enum(context, &item, &tag_name);
+
+	let enum_name = &item.ident;
+	let vis = &item.vis;
+	let (impl_generics, type_generics, where_clause) = item.generics.split_for_impl();
+	let match_arms = item.variants.iter().map(|variant| {
+		let variant_name = &variant.ident;
+		quote!(Self::#variant_name { .. } => #tag_name::#variant_name,)
+	});
+
+	quote! {
+		#tag_enum
+
+		#[automatically_derived]
+		impl #impl_generics #enum_name #type_generics #where_clause {
+			/// Get the tag of the active variant.
+			#vis fn tag(&self) -> #tag_name {
+				match self {
+					#(#match_arms)*
+				}
+			}
+		}
+	}
+}
+
+/// Generate a tag enum with a unit variant for each variant of the enum.
+///
+/// The tag enum implements `Serialize`, `Deserialize` and `JsonSchema` using the tag values of the variants.
+/// It also implements `Display` and `FromStr`, and has an `ALL` constant with all variants.
+///
+/// The `#[serde(catch_all)]` variant is not supported.
+pub(super) fn make_tag_enum(context: &Context, item: &crate::input::Enum, tag_name: &syn::Ident) -> TokenStream {
+	let internal = &context.internal;
+	let serde = &context.serde;
+	let vis = &item.vis;
+
+	let mut variants = Vec::with_capacity(item.variants.len());
+	let mut variant_names = Vec::with_capacity(item.variants.len());
+	let mut tag_value_arms = Vec::with_capacity(item.variants.len());
+	let mut tag_values = Vec::with_capacity(item.variants.len());
+	let mut schema_values = Vec::with_capacity(item.variants.len());
+	for variant in &item.variants {
+		let variant_name = &variant.ident;
+		let doc = format!(" The [`{}::{}`] variant.", item.ident, variant_name);
+		variants.push(quote! {
+			#[doc = #doc]
+			#variant_name,
+		});
+		variant_names.push(variant_name);
+
+		let tag_value = super::variant_tag_value(item, variant).to_expression(internal);
+		tag_value_arms.push(quote!(Self::#variant_name => #tag_value,));
+
+		for value in super::variant_tag_values(item, variant) {
+			let value = value.to_expression(internal);
+			tag_values.push(quote!((#value, #tag_name::#variant_name)));
+			schema_values.push(value);
+		}
+	}
+
+	let other = match item.other_variant() {
+		Some(variant) => {
+			let variant_name = &variant.ident;
+			quote!(::core::option::Option::Some(#tag_name::#variant_name))
+		},
+		None => quote!(::core::option::Option::None),
+	};
+
+	let doc = format!(" The variants of [`{}`], without content.", item.ident);
+
+	#[cfg(feature = "schemars")]
+	let json_schema = match &item.attr.derive_json_schema {
+		Some(_) => {
+			let schemars = &context.schemars;
+			let open = item.other_variant().is_some();
+			quote! {
+				#[automatically_derived]
+				impl #schemars::JsonSchema for #tag_name {
+					fn schema_name() -> ::std::string::String {
+						#internal::string(::core::any::type_name::<Self>())
+					}
+
+					fn schema_id() -> ::std::borrow::Cow<'static, ::core::primitive::str> {
+						::std::borrow::Cow::Borrowed(::core::any::type_name::<Self>())
+					}
+
+					fn json_schema(_generator: &mut #schemars::gen::SchemaGenerator) -> #schemars::schema::Schema {
+						#internal::enum_value(&[#(#schema_values),*], #open)
+					}
+				}
+			}
+		},
+		None => TokenStream::new(),
+	};
+	#[cfg(not(feature = "schemars"))]
+	let json_schema = {
+		let _ = schema_values;
+		TokenStream::new()
+	};
+
+	quote! {
+		#[doc = #doc]
+		#[derive(
+			::core::fmt::Debug,
+			::core::clone::Clone,
+			::core::marker::Copy,
+			::core::cmp::PartialEq,
+			::core::cmp::Eq,
+			::core::hash::Hash,
+		)]
+		#vis enum #tag_name {
+			#(#variants)*
+		}
+
+		#[automatically_derived]
+		impl #tag_name {
+			/// All variants, in declaration order.
+			#vis const ALL: &'static [Self] = &[#(Self::#variant_names),*];
+		}
+
+		#[automatically_derived]
+		impl ::core::fmt::Display for #tag_name {
+			fn fmt(&self, f: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+				let value: #internal::TagValue = match self {
+					#(#tag_value_arms)*
+				};
+				::core::fmt::Display::fmt(&value, f)
+			}
+		}
+
+		#[automatically_derived]
+		impl ::core::str::FromStr for #tag_name {
+			type Err = #internal::ParseTagError;
+
+			fn from_str(input: &::core::primitive::str) -> ::core::result::Result<Self, Self::Err> {
+				const TAG_VALUES: &[(#internal::TagValue, #tag_name)] = &[#(#tag_values),*];
+				#internal::parse_tag_value(input, TAG_VALUES, #other)
+			}
+		}
+
+		#[automatically_derived]
+		impl #serde::Serialize for #tag_name {
+			fn serialize<S: #serde::Serializer>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error> {
+				let value: #internal::TagValue = match self {
+					#(#tag_value_arms)*
+				};
+				#serde::Serialize::serialize(&value, serializer)
+			}
+		}
+
+		#[automatically_derived]
+		impl<'de> #serde::Deserialize<'de> for #tag_name {
+			fn deserialize<D: #serde::Deserializer<'de>>(deserializer: D) -> ::core::result::Result<Self, D::Error> {
+				const TAG_VALUES: &[(#internal::TagValue, #tag_name)] = &[#(#tag_values),*];
+				#internal::deserialize_tag_value(deserializer, TAG_VALUES, #other)
+			}
+		}
+
+		#json_schema
+	}
+}
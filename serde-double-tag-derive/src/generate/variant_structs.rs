@@ -0,0 +1,52 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+use crate::{util, Context};
+
+/// Generate a public struct for each struct variant of an enum.
+///
+/// The structs are named after the enum and the variant, and have the same fields as the variant.
+/// This also generates conversions between the structs and the enum.
+pub fn impl_variant_structs(context: &mut Context, item: crate::input::Enum) -> TokenStream {
+	let enum_name = &item.ident;
+	let (impl_generics, type_generics, where_clause) = item.generics.split_for_impl();
+
+	let mut tokens = TokenStream::new();
+	for variant in &item.variants {
+		if !matches!(variant.fields, crate::input::Fields::Struct(_)) || variant.attr.catch_all.is_some() {
+			continue;
+		}
+		let variant_name = &variant.ident;
+		let struct_name = format_ident!("{}{}", enum_name, variant_name);
+		let fields = super::fields_expression(&variant.fields);
+		let generics = util::prune_generics(&item.generics, variant.fields.iter_types());
+		let (_impl_generics, struct_type_generics, _where_clause) = generics.split_for_impl();
+
+		let definition = super::payload_struct(context, &item, variant, &struct_name);
+		tokens.extend(quote! {
+			#definition
+
+			#[automatically_derived]
+			impl #impl_generics ::core::convert::From<#struct_name #struct_type_generics> for #enum_name #type_generics #where_clause {
+				fn from(value: #struct_name #struct_type_generics) -> Self {
+					let #struct_name #fields = value;
+					Self::#variant_name #fields
+				}
+			}
+
+			#[automatically_derived]
+			impl #impl_generics ::core::convert::TryFrom<#enum_name #type_generics> for #struct_name #struct_type_generics #where_clause {
+				type Error = #enum_name #type_generics;
+
+				/// Get the content of the variant, or give back the enum if a different variant is active.
+				fn try_from(value: #enum_name #type_generics) -> ::core::result::Result<Self, Self::Error> {
+					match value {
+						#enum_name::#variant_name #fields => ::core::result::Result::Ok(Self #fields),
+						other => ::core::result::Result::Err(other),
+					}
+				}
+			}
+		});
+	}
+	tokens
+}
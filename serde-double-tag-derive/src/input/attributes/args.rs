@@ -105,6 +105,21 @@ impl<K, V> KeyValueArg<K, V> {
 		}
 	}
 
+	pub fn map_value<F, NewValue>(self, fun: F) -> KeyValueArg<K, NewValue>
+	where
+		F: FnOnce(V) -> NewValue,
+	{
+		KeyValueArg {
+			pound: self.pound,
+			bracket: self.bracket,
+			attr_path: self.attr_path,
+			delimiter: self.delimiter,
+			key: self.key,
+			eq: self.eq,
+			value: fun(self.value),
+		}
+	}
+
 	pub fn map_key<F, NewKey>(self, fun: F) -> KeyValueArg<NewKey, V>
 	where
 		F: FnOnce(K) -> NewKey,
@@ -258,6 +273,14 @@ where
 		return Ok((None, rest));
 	}
 
+	// Input begins with the expected keyword, but it is the key of a key-value argument.
+	let fork = input.fork();
+	fork.parse::<K>()?;
+	if fork.peek(syn::token::Eq) {
+		let rest = input.parse()?;
+		return Ok((None, rest));
+	}
+
 	// Input DOES begin with the expected keyword.
 	let keyword = input.parse()?;
 
@@ -10,8 +10,21 @@ pub struct EnumAttributes {
 	pub rename: Option<KeyValueArg<keyword::rename, syn::LitStr>>,
 	pub rename_all: Option<KeyValueArg<keyword::rename_all, RenameRule>>,
 	pub rename_all_fields: Option<KeyValueArg<keyword::rename_all_fields, RenameRule>>,
+	pub rename_all_content: Option<KeyValueArg<keyword::rename_all_content, RenameRule>>,
 	pub deny_unknown_fields: Option<KeywordArg<keyword::deny_unknown_fields>>,
+	pub default: Option<KeywordArg<keyword::default>>,
 	pub tag: Option<KeyValueArg<keyword::tag, syn::LitStr>>,
+	pub content: Option<KeyValueArg<keyword::content, syn::LitStr>>,
+	pub discriminant_as_tag: Option<KeywordArg<keyword::discriminant_as_tag>>,
+	pub infer_tag: Option<KeywordArg<keyword::infer_tag>>,
+	pub validate_inactive: Option<KeywordArg<keyword::validate_inactive>>,
+	pub inactive_content: Option<KeyValueArg<keyword::inactive_content, AllowOrDeny>>,
+	pub null_inactive: Option<KeywordArg<keyword::null_inactive>>,
+	pub unit_content: Option<KeyValueArg<keyword::unit_content, UnitContent>>,
+	pub empty_as_unit: Option<KeywordArg<keyword::empty_as_unit>>,
+	pub all_variants: Option<KeyValueArg<keyword::all_variants, syn::LitStr>>,
+	pub tag_enum: Option<KeyValueArg<keyword::tag_enum, syn::LitStr>>,
+	pub derive_json_schema: Option<KeywordArg<keyword::derive_json_schema>>,
 }
 
 impl EnumAttributes {
@@ -29,8 +42,21 @@ impl EnumAttributes {
 				&mut self.rename,
 				&mut self.rename_all,
 				&mut self.rename_all_fields,
+				&mut self.rename_all_content,
 				&mut self.deny_unknown_fields,
+				&mut self.default,
 				&mut self.tag,
+				&mut self.content,
+				&mut self.discriminant_as_tag,
+				&mut self.infer_tag,
+				&mut self.validate_inactive,
+				&mut self.inactive_content,
+				&mut self.null_inactive,
+				&mut self.unit_content,
+				&mut self.empty_as_unit,
+				&mut self.all_variants,
+				&mut self.tag_enum,
+				&mut self.derive_json_schema,
 			]);
 		}
 	}
@@ -42,14 +68,40 @@ impl quote::ToTokens for EnumAttributes {
 			rename,
 			rename_all,
 			rename_all_fields,
+			rename_all_content,
 			deny_unknown_fields,
+			default,
 			tag,
+			content,
+			discriminant_as_tag,
+			infer_tag,
+			validate_inactive,
+			inactive_content,
+			null_inactive,
+			unit_content,
+			empty_as_unit,
+			all_variants,
+			tag_enum,
+			derive_json_schema,
 		} = self;
 		rename.to_tokens(tokens);
 		rename_all.to_tokens(tokens);
 		rename_all_fields.to_tokens(tokens);
+		rename_all_content.to_tokens(tokens);
 		deny_unknown_fields.to_tokens(tokens);
+		default.to_tokens(tokens);
 		tag.to_tokens(tokens);
+		content.to_tokens(tokens);
+		discriminant_as_tag.to_tokens(tokens);
+		infer_tag.to_tokens(tokens);
+		validate_inactive.to_tokens(tokens);
+		inactive_content.to_tokens(tokens);
+		null_inactive.to_tokens(tokens);
+		unit_content.to_tokens(tokens);
+		empty_as_unit.to_tokens(tokens);
+		all_variants.to_tokens(tokens);
+		tag_enum.to_tokens(tokens);
+		derive_json_schema.to_tokens(tokens);
 	}
 }
 
@@ -57,6 +109,12 @@ impl quote::ToTokens for EnumAttributes {
 pub struct VariantAttributes {
 	pub rename: Option<KeyValueArg<keyword::rename, syn::LitStr>>,
 	pub rename_all: Option<KeyValueArg<keyword::rename_all, RenameRule>>,
+	pub alias: Vec<KeyValueArg<keyword::alias, syn::LitStr>>,
+	pub tag_value: Option<KeyValueArg<keyword::tag_value, TagValue>>,
+	pub content: Option<KeyValueArg<keyword::content, syn::LitStr>>,
+	pub other: Option<KeywordArg<keyword::other>>,
+	pub catch_all: Option<KeywordArg<keyword::catch_all>>,
+	pub default: Option<KeywordArg<keyword::default>>,
 }
 
 impl VariantAttributes {
@@ -70,22 +128,66 @@ impl VariantAttributes {
 
 	fn parse_one(&mut self, context: &mut Context, attr: syn::Attribute) {
 		if let Some(mut parser) = args::AttrParser::new(context, attr, "serde") {
-			parser.parse(context, [&mut self.rename, &mut self.rename_all]);
+			parser.parse(context, [
+				&mut self.rename,
+				&mut self.rename_all,
+				&mut self.alias,
+				&mut self.tag_value,
+				&mut self.content,
+				&mut self.other,
+				&mut self.catch_all,
+				&mut self.default,
+			]);
 		}
 	}
 }
 
 impl quote::ToTokens for VariantAttributes {
 	fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
-		let Self { rename, rename_all } = self;
+		let Self {
+			rename,
+			rename_all,
+			alias,
+			tag_value,
+			content,
+			other,
+			catch_all,
+			default,
+		} = self;
 		rename.to_tokens(tokens);
 		rename_all.to_tokens(tokens);
+		for alias in alias {
+			alias.to_tokens(tokens);
+		}
+		tag_value.to_tokens(tokens);
+		content.to_tokens(tokens);
+		other.to_tokens(tokens);
+		catch_all.to_tokens(tokens);
+		default.to_tokens(tokens);
 	}
 }
 
 #[derive(Clone, Default)]
 pub struct FieldAttributes {
 	pub rename: Option<KeyValueArg<keyword::rename, syn::LitStr>>,
+	pub default: Option<KeywordArg<keyword::default>>,
+	pub default_path: Option<KeyValueArg<keyword::default, PathLit>>,
+	pub skip: Option<KeywordArg<keyword::skip>>,
+	pub skip_serializing: Option<KeywordArg<keyword::skip_serializing>>,
+	pub skip_deserializing: Option<KeywordArg<keyword::skip_deserializing>>,
+	pub skip_serializing_if: Option<KeyValueArg<keyword::skip_serializing_if, PathLit>>,
+	pub with: Option<KeyValueArg<keyword::with, PathLit>>,
+	pub serialize_with: Option<KeyValueArg<keyword::serialize_with, PathLit>>,
+	pub deserialize_with: Option<KeyValueArg<keyword::deserialize_with, PathLit>>,
+
+	/// The `#[schemars(with = "...")]` attribute.
+	pub schema_type: Option<KeyValueArg<keyword::with, TypeLit>>,
+
+	/// The `#[schemars(schema_with = "...")]` attribute.
+	pub schema_with: Option<KeyValueArg<keyword::schema_with, PathLit>>,
+
+	/// Emit the `#[schemars(...)]` attributes, only allowed on structs that derive `JsonSchema`.
+	pub emit_schemars: bool,
 }
 
 impl FieldAttributes {
@@ -94,29 +196,137 @@ impl FieldAttributes {
 		for attr in input {
 			output.parse_one(context, attr)
 		}
+		if let (Some(_), Some(default_path)) = (&output.default, &output.default_path) {
+			context.spanned_error(&default_path.key, "attribute `default` already set before");
+		}
+		if let Some(with) = &output.with {
+			if output.serialize_with.is_some() || output.deserialize_with.is_some() {
+				context.spanned_error(
+					&with.key,
+					"attribute `with` can not be combined with `serialize_with` or `deserialize_with`",
+				);
+			}
+		}
+		if let (Some(_), Some(schema_with)) = (&output.schema_type, &output.schema_with) {
+			context.spanned_error(
+				&schema_with.key,
+				"attribute `schema_with` can not be combined with `with` in #[schemars(...)]",
+			);
+		}
 		output
 	}
 
 	fn parse_one(&mut self, context: &mut Context, attr: syn::Attribute) {
-		if let Some(mut parser) = args::AttrParser::new(context, attr, "serde") {
-			parser.parse(context, [&mut self.rename]);
+		if attr.path().is_ident("schemars") {
+			if let Some(mut parser) = args::AttrParser::new(context, attr, "schemars") {
+				parser.parse(context, [&mut self.schema_type, &mut self.schema_with]);
+			}
+		} else if let Some(mut parser) = args::AttrParser::new(context, attr, "serde") {
+			// The `default` keyword must be tried before `default = "..."`.
+			parser.parse(context, [
+				&mut self.rename,
+				&mut self.default,
+				&mut self.default_path,
+				&mut self.skip,
+				&mut self.skip_serializing,
+				&mut self.skip_deserializing,
+				&mut self.skip_serializing_if,
+				&mut self.with,
+				&mut self.serialize_with,
+				&mut self.deserialize_with,
+			]);
+		}
+	}
+
+	/// Check if the field is skipped when serializing.
+	pub fn skips_serializing(&self) -> bool {
+		self.skip.is_some() || self.skip_serializing.is_some()
+	}
+
+	/// Check if the field is skipped when deserializing.
+	pub fn skips_deserializing(&self) -> bool {
+		self.skip.is_some() || self.skip_deserializing.is_some()
+	}
+
+	/// Get an expression for the default value of the field, if it has `#[serde(default)]` or `#[serde(default = "...")]`.
+	#[cfg(feature = "schemars")]
+	pub fn default_expression(&self) -> Option<proc_macro2::TokenStream> {
+		if let Some(default_path) = &self.default_path {
+			let path = &default_path.value.path;
+			Some(quote::quote!(#path()))
+		} else if self.default.is_some() {
+			Some(quote::quote!(::core::default::Default::default()))
+		} else {
+			None
 		}
 	}
 }
 
 impl quote::ToTokens for FieldAttributes {
 	fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
-		let Self { rename } = self;
+		let Self {
+			rename,
+			default,
+			default_path,
+			skip,
+			skip_serializing,
+			skip_deserializing,
+			skip_serializing_if,
+			with,
+			serialize_with,
+			deserialize_with,
+			schema_type,
+			schema_with,
+			emit_schemars,
+		} = self;
 		rename.to_tokens(tokens);
+		default.to_tokens(tokens);
+		default_path.to_tokens(tokens);
+		skip.to_tokens(tokens);
+		skip_serializing.to_tokens(tokens);
+		skip_deserializing.to_tokens(tokens);
+		skip_serializing_if.to_tokens(tokens);
+		with.to_tokens(tokens);
+		serialize_with.to_tokens(tokens);
+		deserialize_with.to_tokens(tokens);
+		if *emit_schemars {
+			schema_type.to_tokens(tokens);
+			schema_with.to_tokens(tokens);
+		}
 	}
 }
 
 pub mod keyword {
 	syn::custom_keyword!(rename);
+	syn::custom_keyword!(default);
+	syn::custom_keyword!(skip);
+	syn::custom_keyword!(skip_serializing);
+	syn::custom_keyword!(skip_deserializing);
+	syn::custom_keyword!(skip_serializing_if);
+	syn::custom_keyword!(with);
+	syn::custom_keyword!(serialize_with);
+	syn::custom_keyword!(deserialize_with);
+	syn::custom_keyword!(schema_with);
 	syn::custom_keyword!(rename_all);
 	syn::custom_keyword!(rename_all_fields);
+	syn::custom_keyword!(rename_all_content);
 	syn::custom_keyword!(deny_unknown_fields);
 	syn::custom_keyword!(tag);
+	syn::custom_keyword!(alias);
+	syn::custom_keyword!(content);
+	syn::custom_keyword!(other);
+	syn::custom_keyword!(catch_all);
+	syn::custom_keyword!(tag_value);
+	syn::custom_keyword!(discriminant_as_tag);
+	syn::custom_keyword!(infer_tag);
+	syn::custom_keyword!(validate_inactive);
+	syn::custom_keyword!(inactive_content);
+	syn::custom_keyword!(null_inactive);
+	syn::custom_keyword!(unit_content);
+	syn::custom_keyword!(empty_as_unit);
+	syn::custom_keyword!(all_variants);
+	syn::custom_keyword!(tag_enum);
+	syn::custom_keyword!(derive_json_schema);
 }
 
 #[derive(Clone, Copy)]
@@ -128,8 +338,8 @@ pub struct RenameRule {
 impl syn::parse::Parse for RenameRule {
 	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
 		let literal: syn::LitStr = input.parse()?;
-		let rule = crate::util::RenameRule::from_str(&literal.value())
-			.map_err(|e| syn::Error::new_spanned(&literal, e))?;
+		let rule =
+			crate::util::RenameRule::from_str(&literal.value()).map_err(|e| syn::Error::new_spanned(&literal, e))?;
 		Ok(Self {
 			rule,
 			span: literal.span(),
@@ -144,3 +354,191 @@ impl quote::ToTokens for RenameRule {
 		tokens.extend([proc_macro2::TokenTree::Literal(literal)]);
 	}
 }
+
+/// A path given as string literal, like `#[serde(default = "path")]`.
+#[derive(Clone)]
+pub struct PathLit {
+	pub path: syn::ExprPath,
+	pub literal: syn::LitStr,
+}
+
+impl PathLit {
+	/// Create a path literal from a string with the given span.
+	///
+	/// Panics if the string is not a valid path.
+	pub fn new(path: &str, span: Span) -> Self {
+		let literal = syn::LitStr::new(path, span);
+		Self {
+			path: literal.parse().unwrap(),
+			literal,
+		}
+	}
+}
+
+impl syn::parse::Parse for PathLit {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		let literal: syn::LitStr = input.parse()?;
+		let path = literal.parse()?;
+		Ok(Self { path, literal })
+	}
+}
+
+impl quote::ToTokens for PathLit {
+	fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+		self.literal.to_tokens(tokens);
+	}
+}
+
+/// A type given as string literal, like `#[schemars(with = "Type")]`.
+#[derive(Clone)]
+pub struct TypeLit {
+	#[cfg_attr(not(feature = "schemars"), allow(unused))]
+	pub ty: syn::Type,
+	pub literal: syn::LitStr,
+}
+
+impl syn::parse::Parse for TypeLit {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		let literal: syn::LitStr = input.parse()?;
+		let ty = literal.parse()?;
+		Ok(Self { ty, literal })
+	}
+}
+
+impl quote::ToTokens for TypeLit {
+	fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+		self.literal.to_tokens(tokens);
+	}
+}
+
+#[derive(Clone, Copy)]
+pub struct AllowOrDeny {
+	pub deny: bool,
+	pub span: Span,
+}
+
+impl syn::parse::Parse for AllowOrDeny {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		let literal: syn::LitStr = input.parse()?;
+		let deny = match literal.value().as_str() {
+			"allow" => false,
+			"deny" => true,
+			_ => return Err(syn::Error::new_spanned(&literal, "expected \"allow\" or \"deny\"")),
+		};
+		Ok(Self {
+			deny,
+			span: literal.span(),
+		})
+	}
+}
+
+impl quote::ToTokens for AllowOrDeny {
+	fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+		let value = if self.deny { "deny" } else { "allow" };
+		let mut literal = proc_macro2::Literal::string(value);
+		literal.set_span(self.span);
+		tokens.extend([proc_macro2::TokenTree::Literal(literal)]);
+	}
+}
+
+#[derive(Clone, Copy)]
+pub struct UnitContent {
+	pub kind: UnitContentKind,
+	pub span: Span,
+}
+
+/// How the content of unit variants is encoded.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum UnitContentKind {
+	/// The content field is omitted.
+	Omit,
+
+	/// The content field is `null`.
+	Null,
+
+	/// The content field is an empty map.
+	Empty,
+}
+
+impl UnitContentKind {
+	fn as_str(self) -> &'static str {
+		match self {
+			Self::Omit => "omit",
+			Self::Null => "null",
+			Self::Empty => "empty",
+		}
+	}
+}
+
+impl syn::parse::Parse for UnitContent {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		let literal: syn::LitStr = input.parse()?;
+		let kind = match literal.value().as_str() {
+			"omit" => UnitContentKind::Omit,
+			"null" => UnitContentKind::Null,
+			"empty" => UnitContentKind::Empty,
+			_ => {
+				return Err(syn::Error::new_spanned(
+					&literal,
+					"expected \"omit\", \"null\" or \"empty\"",
+				))
+			},
+		};
+		Ok(Self {
+			kind,
+			span: literal.span(),
+		})
+	}
+}
+
+impl quote::ToTokens for UnitContent {
+	fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+		let mut literal = proc_macro2::Literal::string(self.kind.as_str());
+		literal.set_span(self.span);
+		tokens.extend([proc_macro2::TokenTree::Literal(literal)]);
+	}
+}
+
+#[derive(Clone)]
+pub struct TagValue {
+	pub value: crate::util::TagValue,
+	pub negative: Option<syn::token::Minus>,
+	pub literal: syn::Lit,
+}
+
+impl syn::parse::Parse for TagValue {
+	fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+		let negative: Option<syn::token::Minus> = input.parse()?;
+		let literal: syn::Lit = input.parse()?;
+		let value = match (&literal, negative) {
+			(syn::Lit::Int(literal), negative) => {
+				crate::util::TagValue::Int(crate::util::TagValue::parse_int_literal(literal, negative.is_some())?)
+			},
+			(syn::Lit::Str(literal), None) => crate::util::TagValue::Str(literal.value()),
+			(syn::Lit::Bool(literal), None) => crate::util::TagValue::Bool(literal.value),
+			_ => {
+				return Err(syn::Error::new_spanned(
+					&literal,
+					"expected a string, integer or boolean literal",
+				))
+			},
+		};
+		Ok(Self {
+			value,
+			negative,
+			literal,
+		})
+	}
+}
+
+impl quote::ToTokens for TagValue {
+	fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
+		let Self {
+			value: _,
+			negative,
+			literal,
+		} = self;
+		negative.to_tokens(tokens);
+		literal.to_tokens(tokens);
+	}
+}
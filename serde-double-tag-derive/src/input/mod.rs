@@ -7,6 +7,7 @@ pub mod attributes;
 
 pub struct Enum {
 	pub attr: attributes::EnumAttributes,
+	pub vis: syn::Visibility,
 	pub ident: syn::Ident,
 	pub generics: syn::Generics,
 	pub variants: syn::punctuated::Punctuated<Variant, syn::token::Comma>,
@@ -28,19 +29,220 @@ impl Enum {
 	}
 
 	fn from_syn(context: &mut Context, input: syn::ItemEnum) -> Self {
-		Self {
+		let output = Self {
 			attr: attributes::EnumAttributes::from_syn(context, input.attrs),
+			vis: input.vis,
 			ident: input.ident,
 			generics: input.generics,
 			variants: Variant::from_punctuated(context, input.variants),
+		};
+		output.validate(context);
+		output
+	}
+
+	/// Check the combination of attributes on the enum and the variants.
+	fn validate(&self, context: &mut Context) {
+		if let Some(content) = &self.attr.content {
+			if !content.value.value().contains("{tag}") {
+				context.spanned_error(
+					&content.value,
+					"the content field name template must contain a `{tag}` placeholder",
+				);
+			}
+			if let Some(rename_all_content) = &self.attr.rename_all_content {
+				context.spanned_error(
+					&rename_all_content.key,
+					"#[serde(rename_all_content)] can not be combined with #[serde(content)] on the enum",
+				);
+			}
+		}
+		#[cfg(not(feature = "schemars"))]
+		if let Some(derive_json_schema) = &self.attr.derive_json_schema {
+			context.spanned_error(
+				&derive_json_schema.keyword,
+				"#[serde(derive_json_schema)] requires the `schemars` feature",
+			);
+		}
+		if let (Some(rename_all_content), Some(_)) = (&self.attr.rename_all_content, self.catch_all_variant()) {
+			context.spanned_error(
+				&rename_all_content.key,
+				"#[serde(rename_all_content)] can not be combined with a #[serde(catch_all)] variant",
+			);
+		}
+
+		let mut seen_other = false;
+		let mut seen_catch_all = false;
+		for variant in &self.variants {
+			if let (Some(_), Some(discriminant)) = (&self.attr.discriminant_as_tag, &variant.discriminant) {
+				if let Err(e) = parse_discriminant(discriminant) {
+					context.syn_error(e);
+				}
+			}
+			if let Some(other) = &variant.attr.other {
+				if !variant.fields.is_unit() {
+					context.spanned_error(&other.keyword, "#[serde(other)] must be on a unit variant");
+				}
+				if seen_other {
+					context.spanned_error(&other.keyword, "#[serde(other)] can only be used on one variant");
+				}
+				if let Some(content) = &variant.attr.content {
+					context.spanned_error(
+						&content.key,
+						"#[serde(content)] can not be used on a #[serde(other)] variant",
+					);
+				}
+				seen_other = true;
+			}
+			if let Some(catch_all) = &variant.attr.catch_all {
+				match variant.catch_all_fields() {
+					None => context.spanned_error(
+						&catch_all.keyword,
+						"#[serde(catch_all)] must be on a variant with two fields: `{ tag, content }` or `(tag, content)`",
+					),
+					Some(fields) => {
+						if crate::util::type_uses_generic(fields.tag, &self.generics) {
+							context.spanned_error(
+								fields.tag,
+								"the tag field of a #[serde(catch_all)] variant can not use generic parameters",
+							);
+						}
+					},
+				}
+				if let Some(content) = &variant.attr.content {
+					context.spanned_error(
+						&content.key,
+						"#[serde(content)] can not be used on a #[serde(catch_all)] variant",
+					);
+				}
+				for alias in &variant.attr.alias {
+					context.spanned_error(
+						&alias.key,
+						"#[serde(alias)] can not be used on a #[serde(catch_all)] variant",
+					);
+				}
+				if let Some(tag_value) = &variant.attr.tag_value {
+					context.spanned_error(
+						&tag_value.key,
+						"#[serde(tag_value)] can not be used on a #[serde(catch_all)] variant",
+					);
+				}
+				if let Some(default) = &variant.attr.default {
+					context.spanned_error(
+						&default.keyword,
+						"#[serde(default)] can not be used on a #[serde(catch_all)] variant",
+					);
+				}
+				if seen_catch_all {
+					context.spanned_error(
+						&catch_all.keyword,
+						"#[serde(catch_all)] can only be used on one variant",
+					);
+				}
+				seen_catch_all = true;
+			}
+			if let Some(default) = &variant.attr.default {
+				if variant.fields.is_unit() {
+					context.spanned_error(
+						&default.keyword,
+						"#[serde(default)] must be on a tuple or struct variant",
+					);
+				}
+			}
+		}
+		if seen_other && seen_catch_all {
+			context.error(
+				self.ident.span(),
+				"#[serde(other)] and #[serde(catch_all)] can not be used on the same enum",
+			);
+		}
+
+		// The tag of the `#[serde(catch_all)]` variant is captured as string, so all tag values must be strings.
+		if seen_catch_all {
+			if let Some(discriminant_as_tag) = &self.attr.discriminant_as_tag {
+				context.spanned_error(
+					&discriminant_as_tag.keyword,
+					"#[serde(discriminant_as_tag)] can not be combined with a #[serde(catch_all)] variant",
+				);
+			}
+			for variant in &self.variants {
+				if let Some(tag_value) = &variant.attr.tag_value {
+					if !matches!(tag_value.value.value, crate::util::TagValue::Str(_)) {
+						context.spanned_error(
+							&tag_value.value.literal,
+							"integer and boolean tag values can not be combined with a #[serde(catch_all)] variant",
+						);
+					}
+				}
+			}
 		}
 	}
+
+	/// Get the discriminant of a variant as tag value, if the enum has `#[serde(discriminant_as_tag)]`.
+	///
+	/// Variants without explicit discriminant get the value of the previous variant plus one,
+	/// just like the compiler assigns discriminants.
+	pub fn discriminant_tag(&self, variant: &Variant) -> Option<crate::util::TagValue> {
+		self.attr.discriminant_as_tag.as_ref()?;
+		let mut value = 0;
+		for other in &self.variants {
+			if let Some(discriminant) = &other.discriminant {
+				value = parse_discriminant(discriminant).ok()?;
+			}
+			if other.ident == variant.ident {
+				return Some(crate::util::TagValue::Int(value));
+			}
+			value += 1;
+		}
+		None
+	}
+
+	/// Get the variant marked with `#[serde(catch_all)]`, if any.
+	pub fn catch_all_variant(&self) -> Option<&Variant> {
+		self.variants.iter().find(|variant| variant.attr.catch_all.is_some())
+	}
+
+	/// Get the variant marked with `#[serde(other)]`, if any.
+	pub fn other_variant(&self) -> Option<&Variant> {
+		self.variants.iter().find(|variant| variant.attr.other.is_some())
+	}
+}
+
+/// Parse an enum discriminant as integer tag value.
+///
+/// Only (negated) integer literals are supported.
+fn parse_discriminant(expr: &syn::Expr) -> syn::Result<i128> {
+	match expr {
+		syn::Expr::Lit(syn::ExprLit {
+			lit: syn::Lit::Int(literal),
+			..
+		}) => crate::util::TagValue::parse_int_literal(literal, false),
+		syn::Expr::Unary(syn::ExprUnary {
+			op: syn::UnOp::Neg(_),
+			expr,
+			..
+		}) => match &**expr {
+			syn::Expr::Lit(syn::ExprLit {
+				lit: syn::Lit::Int(literal),
+				..
+			}) => crate::util::TagValue::parse_int_literal(literal, true),
+			_ => Err(unsupported_discriminant(expr)),
+		},
+		_ => Err(unsupported_discriminant(expr)),
+	}
+}
+
+fn unsupported_discriminant(expr: &syn::Expr) -> syn::Error {
+	syn::Error::new_spanned(
+		expr,
+		"only integer literals are supported as discriminant with #[serde(discriminant_as_tag)]",
+	)
 }
 
 pub struct Variant {
 	pub attr: attributes::VariantAttributes,
 	pub ident: syn::Ident,
 	pub fields: Fields,
+	pub discriminant: Option<syn::Expr>,
 }
 
 impl Variant {
@@ -49,6 +251,7 @@ impl Variant {
 			attr: attributes::VariantAttributes::from_syn(context, input.attrs),
 			ident: input.ident,
 			fields: Fields::from_syn(context, input.fields),
+			discriminant: input.discriminant.map(|(_eq, expr)| expr),
 		}
 	}
 
@@ -66,6 +269,35 @@ impl Variant {
 			.collect()
 	}
 
+	/// Get the types of the tag and content fields of a `#[serde(catch_all)]` variant.
+	///
+	/// Returns `None` if the variant does not have the right fields.
+	pub fn catch_all_fields(&self) -> Option<CatchAllFields<'_>> {
+		match &self.fields {
+			Fields::Unit => None,
+			Fields::Tuple(fields) => {
+				if fields.fields.len() != 2 {
+					return None;
+				}
+				Some(CatchAllFields {
+					tag: &fields.fields[0].ty,
+					content: &fields.fields[1].ty,
+				})
+			},
+			Fields::Struct(fields) => {
+				if fields.fields.len() != 2 {
+					return None;
+				}
+				let tag = fields.fields.iter().find(|field| field.ident == "tag")?;
+				let content = fields.fields.iter().find(|field| field.ident == "content")?;
+				Some(CatchAllFields {
+					tag: &tag.ty,
+					content: &content.ty,
+				})
+			},
+		}
+	}
+
 	#[allow(clippy::manual_map)]
 	pub fn rename_all_rule(
 		&self,
@@ -81,6 +313,12 @@ impl Variant {
 	}
 }
 
+/// The types of the tag and content fields of a `#[serde(catch_all)]` variant.
+pub struct CatchAllFields<'a> {
+	pub tag: &'a syn::Type,
+	pub content: &'a syn::Type,
+}
+
 #[derive(Clone)]
 pub enum Fields {
 	Unit,
@@ -111,6 +349,41 @@ impl Fields {
 		}
 	}
 
+	/// Set the visibility of all fields.
+	pub fn with_visibility(&self, vis: &syn::Visibility) -> Self {
+		let mut output = self.clone();
+		match &mut output {
+			Self::Unit => (),
+			Self::Tuple(x) => x.fields.iter_mut().for_each(|field| field.vis = vis.clone()),
+			Self::Struct(x) => x.fields.iter_mut().for_each(|field| field.vis = vis.clone()),
+		}
+		output
+	}
+
+	/// Emit the `#[schemars(...)]` attributes of all fields, for structs that derive `JsonSchema`.
+	#[cfg(feature = "schemars")]
+	pub fn with_schemars_attributes(&self) -> Self {
+		let mut output = self.clone();
+		match &mut output {
+			Self::Unit => (),
+			Self::Tuple(x) => x.fields.iter_mut().for_each(|field| field.attrs.emit_schemars = true),
+			Self::Struct(x) => x.fields.iter_mut().for_each(|field| field.attrs.emit_schemars = true),
+		}
+		output
+	}
+
+	/// Iterate over the attributes and types of all fields.
+	pub fn iter_fields(&self) -> impl Iterator<Item = (&attributes::FieldAttributes, &syn::Type)> {
+		let (tuple, fields) = match self {
+			Self::Unit => (None, None),
+			Self::Tuple(x) => (Some(&x.fields), None),
+			Self::Struct(x) => (None, Some(&x.fields)),
+		};
+		let tuple = tuple.into_iter().flatten().map(|field| (&field.attrs, &field.ty));
+		let fields = fields.into_iter().flatten().map(|field| (&field.attrs, &field.ty));
+		tuple.chain(fields)
+	}
+
 	pub fn iter_types(&self) -> FieldTypes<'_> {
 		match self {
 			Self::Unit => FieldTypes::Unit,
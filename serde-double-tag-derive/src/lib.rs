@@ -42,7 +42,8 @@ impl Context {
 	}
 
 	fn error(&mut self, span: proc_macro2::Span, message: impl std::fmt::Display) {
-		self.errors.push(syn::Error::new(span, format_args!("serde_double_tag: {message}")))
+		self.errors
+			.push(syn::Error::new(span, format_args!("serde_double_tag: {message}")))
 	}
 
 	fn spanned_error<T: quote::ToTokens>(&mut self, object: &T, message: impl std::fmt::Display) {
@@ -84,7 +85,47 @@ pub fn derive_serialize(tokens: proc_macro::TokenStream) -> proc_macro::TokenStr
 	context.collect_errors(output).into()
 }
 
-#[proc_macro_derive(JsonSchema, attributes(serde))]
+#[proc_macro_derive(AllVariants, attributes(serde))]
+pub fn derive_all_variants(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	let mut context = Context::new(crate_name());
+	let output = match input::Enum::parse2(&mut context, tokens.into()) {
+		Ok(input) => generate::impl_all_variants(&mut context, input),
+		Err(()) => proc_macro2::TokenStream::new(),
+	};
+	context.collect_errors(output).into()
+}
+
+#[proc_macro_derive(TagEnum, attributes(serde))]
+pub fn derive_tag_enum(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	let mut context = Context::new(crate_name());
+	let output = match input::Enum::parse2(&mut context, tokens.into()) {
+		Ok(input) => generate::impl_tag_enum(&mut context, input),
+		Err(()) => proc_macro2::TokenStream::new(),
+	};
+	context.collect_errors(output).into()
+}
+
+#[proc_macro_derive(DoubleTagged, attributes(serde))]
+pub fn derive_double_tagged(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	let mut context = Context::new(crate_name());
+	let output = match input::Enum::parse2(&mut context, tokens.into()) {
+		Ok(input) => generate::impl_double_tagged(&mut context, input),
+		Err(()) => proc_macro2::TokenStream::new(),
+	};
+	context.collect_errors(output).into()
+}
+
+#[proc_macro_derive(VariantStructs, attributes(serde))]
+pub fn derive_variant_structs(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	let mut context = Context::new(crate_name());
+	let output = match input::Enum::parse2(&mut context, tokens.into()) {
+		Ok(input) => generate::impl_variant_structs(&mut context, input),
+		Err(()) => proc_macro2::TokenStream::new(),
+	};
+	context.collect_errors(output).into()
+}
+
+#[proc_macro_derive(JsonSchema, attributes(serde, schemars))]
 #[cfg(feature = "schemars")]
 pub fn derive_json_schema(tokens: proc_macro::TokenStream) -> proc_macro::TokenStream {
 	let mut context = Context::new(crate_name());
@@ -4,6 +4,9 @@ use syn::spanned::Spanned;
 mod rename_rule;
 pub use rename_rule::RenameRule;
 
+mod tag_value;
+pub use tag_value::TagValue;
+
 mod prune_generics;
 pub use prune_generics::prune_generics;
 
@@ -146,6 +149,25 @@ pub fn type_uses_generic(ty: &syn::Type, generics: &syn::Generics) -> bool {
 	visitor.found
 }
 
+/// Check if a type is syntactically an `Option<T>`.
+pub fn is_option_type(ty: &syn::Type) -> bool {
+	let path = match ty {
+		syn::Type::Path(x) if x.qself.is_none() => &x.path,
+		_ => return false,
+	};
+	let segment = match path.segments.last() {
+		Some(x) => x,
+		None => return false,
+	};
+	if segment.ident != "Option" {
+		return false;
+	}
+	match &segment.arguments {
+		syn::PathArguments::AngleBracketed(args) => args.args.len() == 1,
+		_ => false,
+	}
+}
+
 pub fn strip_type_wrappers(ty: &syn::Type) -> &syn::Type {
 	let mut ty = ty;
 	loop {
@@ -0,0 +1,54 @@
+/// A value for the tag field of an enum.
+#[derive(Clone, PartialEq)]
+pub enum TagValue {
+	/// A string tag value.
+	Str(String),
+
+	/// An integer tag value, in the range of `i64` and `u64`.
+	Int(i128),
+
+	/// A boolean tag value.
+	Bool(bool),
+}
+
+impl TagValue {
+	/// The largest allowed integer tag value.
+	pub const MAX_INT: i128 = u64::MAX as i128;
+	/// The smallest allowed integer tag value.
+	pub const MIN_INT: i128 = i64::MIN as i128;
+
+	/// Parse an integer tag value from a literal, optionally negated.
+	pub fn parse_int_literal(literal: &syn::LitInt, negative: bool) -> syn::Result<i128> {
+		let value: i128 = literal.base10_parse()?;
+		let value = if negative { -value } else { value };
+		if !(Self::MIN_INT..=Self::MAX_INT).contains(&value) {
+			return Err(syn::Error::new_spanned(
+				literal,
+				"integer tag values must fit in an `i64` or `u64`",
+			));
+		}
+		Ok(value)
+	}
+
+	/// Generate an expression for the value as `serde_double_tag::internal__::TagValue`.
+	pub fn to_expression(&self, internal: &syn::Path) -> proc_macro2::TokenStream {
+		match self {
+			Self::Str(value) => quote::quote!(#internal::TagValue::Str(#value)),
+			Self::Int(value) => {
+				let value = proc_macro2::Literal::i128_unsuffixed(*value);
+				quote::quote!(#internal::TagValue::Int(#value))
+			},
+			Self::Bool(value) => quote::quote!(#internal::TagValue::Bool(#value)),
+		}
+	}
+}
+
+impl std::fmt::Display for TagValue {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Str(value) => f.write_str(value),
+			Self::Int(value) => value.fmt(f),
+			Self::Bool(value) => value.fmt(f),
+		}
+	}
+}
@@ -0,0 +1,72 @@
+/// Information about the representation of a double tagged enum.
+///
+/// This trait is implemented by the `DoubleTagged` derive macro.
+/// It can be combined with any of the other derive macros of this crate.
+/// Enums with a `#[serde(catch_all)]` variant are not supported, since the tag value of that variant is not known in advance.
+///
+/// It can be used to refer to the tag field and the content fields generically,
+/// without having to repeat the renaming rules of the enum.
+///
+/// ```
+/// # use serde_double_tag::DoubleTagged;
+/// #[derive(serde_double_tag::DoubleTagged)]
+/// #[serde(tag = "species")]
+/// #[serde(rename_all = "snake_case")]
+/// enum Friend {
+///   Human { name: String },
+///   Dog { name: String },
+///   Nobody,
+/// }
+///
+/// assert_eq!(Friend::TAG_FIELD, "species");
+/// assert_eq!(Friend::VARIANTS[1].content_key, Some("dog"));
+///
+/// let friend = Friend::Dog { name: "Scrappy".into() };
+/// assert_eq!(friend.tag_value(), "dog");
+/// assert_eq!(friend.content_key(), Some("dog"));
+/// assert_eq!(Friend::Nobody.content_key(), None);
+/// ```
+pub trait DoubleTagged {
+	/// The name of the tag field.
+	const TAG_FIELD: &'static str;
+
+	/// Information about all variants of the enum, in declaration order.
+	const VARIANTS: &'static [VariantInfo];
+
+	/// Get the tag value of the active variant.
+	///
+	/// For integer and boolean tag values, this is the textual representation of the value.
+	fn tag_value(&self) -> &'static str;
+
+	/// Get the content field of the active variant.
+	///
+	/// Returns `None` if no content field is written for the active variant.
+	/// This is the case for unit variants, unless the enum has `#[serde(unit_content = "null")]` or `#[serde(unit_content = "empty")]`.
+	fn content_key(&self) -> Option<&'static str>;
+}
+
+/// Information about a variant of a double tagged enum.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct VariantInfo {
+	/// The name of the variant in Rust.
+	pub name: &'static str,
+
+	/// The tag value of the variant.
+	///
+	/// For integer and boolean tag values, this is the textual representation of the value.
+	pub tag_value: &'static str,
+
+	/// The name of the content field of the variant.
+	///
+	/// Unit variants also have a content field name.
+	/// With `#[serde(unit_content = "null")]` or `#[serde(unit_content = "empty")]` it is always written,
+	/// otherwise it is omitted when serializing and optional when deserializing.
+	/// This is `None` for the `#[serde(other)]` variant.
+	pub content_key: Option<&'static str>,
+
+	/// Whether the variant has fields.
+	///
+	/// This is `false` for unit variants, even if `#[serde(unit_content)]` writes a `null` or empty content field for them.
+	/// Use [`DoubleTagged::content_key()`] to check if a content field is written for a value.
+	pub has_content: bool,
+}
@@ -0,0 +1,49 @@
+/// Error for a double tagged value where the content field of the active variant is missing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingContent {
+	content_field_name: &'static str,
+}
+
+impl MissingContent {
+	#[doc(hidden)]
+	pub fn new(content_field_name: &'static str) -> Self {
+		Self { content_field_name }
+	}
+
+	/// Get the name of the missing content field.
+	pub fn content_field_name(&self) -> &'static str {
+		self.content_field_name
+	}
+}
+
+impl std::fmt::Display for MissingContent {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(
+			f,
+			"missing content field `{}` for the active variant",
+			self.content_field_name
+		)
+	}
+}
+
+impl std::error::Error for MissingContent {}
+
+/// Error for a string that is not a valid tag value of a double tagged enum.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseTagError {
+	message: String,
+}
+
+impl ParseTagError {
+	pub(crate) fn new(message: String) -> Self {
+		Self { message }
+	}
+}
+
+impl std::fmt::Display for ParseTagError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str(&self.message)
+	}
+}
+
+impl std::error::Error for ParseTagError {}
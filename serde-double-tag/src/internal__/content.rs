@@ -0,0 +1,512 @@
+//! Buffered deserialized content.
+//!
+//! Used to hold on to values from a `MapAccess` that we can not deserialize yet,
+//! because we do not know the type of the value until we have seen the tag.
+
+use serde::de::{Deserialize, Deserializer, Error, IntoDeserializer, Visitor};
+
+/// A buffered value that can be deserialized again later.
+#[derive(Debug, Clone)]
+pub enum Content<'de> {
+	/// A boolean.
+	Bool(bool),
+
+	/// An unsigned 8 bit integer.
+	U8(u8),
+	/// An unsigned 16 bit integer.
+	U16(u16),
+	/// An unsigned 32 bit integer.
+	U32(u32),
+	/// An unsigned 64 bit integer.
+	U64(u64),
+	/// An unsigned 128 bit integer.
+	U128(u128),
+
+	/// A signed 8 bit integer.
+	I8(i8),
+	/// A signed 16 bit integer.
+	I16(i16),
+	/// A signed 32 bit integer.
+	I32(i32),
+	/// A signed 64 bit integer.
+	I64(i64),
+	/// A signed 128 bit integer.
+	I128(i128),
+
+	/// A 32 bit floating point number.
+	F32(f32),
+	/// A 64 bit floating point number.
+	F64(f64),
+
+	/// A single character.
+	Char(char),
+	/// An owned string.
+	String(String),
+	/// A borrowed string.
+	Str(&'de str),
+	/// An owned byte buffer.
+	ByteBuf(Vec<u8>),
+	/// A borrowed byte slice.
+	Bytes(&'de [u8]),
+
+	/// An absent optional value.
+	None,
+	/// A present optional value.
+	Some(Box<Content<'de>>),
+
+	/// A unit value.
+	Unit,
+	/// A newtype struct.
+	Newtype(Box<Content<'de>>),
+	/// A sequence of values.
+	Seq(Vec<Content<'de>>),
+	/// A map of key-value pairs.
+	Map(Vec<(Content<'de>, Content<'de>)>),
+}
+
+impl<'de> Content<'de> {
+	/// Deserialize a value from the content.
+	pub fn deserialize_into<T: Deserialize<'de>, E: Error>(self) -> Result<T, E> {
+		T::deserialize(ContentDeserializer::new(self))
+	}
+
+	/// Convert the content into content that does not borrow from the input.
+	pub fn into_owned(self) -> Content<'static> {
+		match self {
+			Self::Bool(x) => Content::Bool(x),
+			Self::U8(x) => Content::U8(x),
+			Self::U16(x) => Content::U16(x),
+			Self::U32(x) => Content::U32(x),
+			Self::U64(x) => Content::U64(x),
+			Self::U128(x) => Content::U128(x),
+			Self::I8(x) => Content::I8(x),
+			Self::I16(x) => Content::I16(x),
+			Self::I32(x) => Content::I32(x),
+			Self::I64(x) => Content::I64(x),
+			Self::I128(x) => Content::I128(x),
+			Self::F32(x) => Content::F32(x),
+			Self::F64(x) => Content::F64(x),
+			Self::Char(x) => Content::Char(x),
+			Self::String(x) => Content::String(x),
+			Self::Str(x) => Content::String(x.into()),
+			Self::ByteBuf(x) => Content::ByteBuf(x),
+			Self::Bytes(x) => Content::ByteBuf(x.into()),
+			Self::None => Content::None,
+			Self::Some(x) => Content::Some(Box::new(x.into_owned())),
+			Self::Unit => Content::Unit,
+			Self::Newtype(x) => Content::Newtype(Box::new(x.into_owned())),
+			Self::Seq(x) => Content::Seq(x.into_iter().map(Content::into_owned).collect()),
+			Self::Map(x) => Content::Map(
+				x.into_iter()
+					.map(|(key, value)| (key.into_owned(), value.into_owned()))
+					.collect(),
+			),
+		}
+	}
+
+	/// Check if the content is `null`: a unit value or `None`.
+	pub fn is_null(&self) -> bool {
+		matches!(self, Self::Unit | Self::None)
+	}
+
+	/// Get the content as string slice, if it is a string.
+	pub fn as_str(&self) -> Option<&str> {
+		match self {
+			Self::String(x) => Some(x),
+			Self::Str(x) => Some(x),
+			_ => None,
+		}
+	}
+
+	fn unexpected(&self) -> serde::de::Unexpected<'_> {
+		use serde::de::Unexpected;
+		match self {
+			Self::Bool(x) => Unexpected::Bool(*x),
+			Self::U8(x) => Unexpected::Unsigned(u64::from(*x)),
+			Self::U16(x) => Unexpected::Unsigned(u64::from(*x)),
+			Self::U32(x) => Unexpected::Unsigned(u64::from(*x)),
+			Self::U64(x) => Unexpected::Unsigned(*x),
+			Self::U128(_) => Unexpected::Other("128-bit unsigned integer"),
+			Self::I8(x) => Unexpected::Signed(i64::from(*x)),
+			Self::I16(x) => Unexpected::Signed(i64::from(*x)),
+			Self::I32(x) => Unexpected::Signed(i64::from(*x)),
+			Self::I64(x) => Unexpected::Signed(*x),
+			Self::I128(_) => Unexpected::Other("128-bit signed integer"),
+			Self::F32(x) => Unexpected::Float(f64::from(*x)),
+			Self::F64(x) => Unexpected::Float(*x),
+			Self::Char(x) => Unexpected::Char(*x),
+			Self::String(x) => Unexpected::Str(x),
+			Self::Str(x) => Unexpected::Str(x),
+			Self::ByteBuf(x) => Unexpected::Bytes(x),
+			Self::Bytes(x) => Unexpected::Bytes(x),
+			Self::None | Self::Some(_) => Unexpected::Option,
+			Self::Unit => Unexpected::Unit,
+			Self::Newtype(_) => Unexpected::NewtypeStruct,
+			Self::Seq(_) => Unexpected::Seq,
+			Self::Map(_) => Unexpected::Map,
+		}
+	}
+}
+
+impl<'de> Deserialize<'de> for Content<'de> {
+	fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		deserializer.deserialize_any(ContentVisitor)
+	}
+}
+
+impl serde::Serialize for Content<'_> {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		use serde::ser::{SerializeMap, SerializeSeq};
+		match self {
+			Self::Bool(x) => serializer.serialize_bool(*x),
+			Self::U8(x) => serializer.serialize_u8(*x),
+			Self::U16(x) => serializer.serialize_u16(*x),
+			Self::U32(x) => serializer.serialize_u32(*x),
+			Self::U64(x) => serializer.serialize_u64(*x),
+			Self::U128(x) => serializer.serialize_u128(*x),
+			Self::I8(x) => serializer.serialize_i8(*x),
+			Self::I16(x) => serializer.serialize_i16(*x),
+			Self::I32(x) => serializer.serialize_i32(*x),
+			Self::I64(x) => serializer.serialize_i64(*x),
+			Self::I128(x) => serializer.serialize_i128(*x),
+			Self::F32(x) => serializer.serialize_f32(*x),
+			Self::F64(x) => serializer.serialize_f64(*x),
+			Self::Char(x) => serializer.serialize_char(*x),
+			Self::String(x) => serializer.serialize_str(x),
+			Self::Str(x) => serializer.serialize_str(x),
+			Self::ByteBuf(x) => serializer.serialize_bytes(x),
+			Self::Bytes(x) => serializer.serialize_bytes(x),
+			Self::None => serializer.serialize_none(),
+			Self::Some(x) => serializer.serialize_some(x),
+			Self::Unit => serializer.serialize_unit(),
+			Self::Newtype(x) => x.serialize(serializer),
+			Self::Seq(items) => {
+				let mut seq = serializer.serialize_seq(Some(items.len()))?;
+				for item in items {
+					seq.serialize_element(item)?;
+				}
+				seq.end()
+			},
+			Self::Map(entries) => {
+				let mut map = serializer.serialize_map(Some(entries.len()))?;
+				for (key, value) in entries {
+					map.serialize_entry(key, value)?;
+				}
+				map.end()
+			},
+		}
+	}
+}
+
+struct ContentVisitor;
+
+impl<'de> Visitor<'de> for ContentVisitor {
+	type Value = Content<'de>;
+
+	fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+		formatter.write_str("any value")
+	}
+
+	fn visit_bool<E: Error>(self, value: bool) -> Result<Self::Value, E> {
+		Ok(Content::Bool(value))
+	}
+
+	fn visit_i8<E: Error>(self, value: i8) -> Result<Self::Value, E> {
+		Ok(Content::I8(value))
+	}
+
+	fn visit_i16<E: Error>(self, value: i16) -> Result<Self::Value, E> {
+		Ok(Content::I16(value))
+	}
+
+	fn visit_i32<E: Error>(self, value: i32) -> Result<Self::Value, E> {
+		Ok(Content::I32(value))
+	}
+
+	fn visit_i64<E: Error>(self, value: i64) -> Result<Self::Value, E> {
+		Ok(Content::I64(value))
+	}
+
+	fn visit_i128<E: Error>(self, value: i128) -> Result<Self::Value, E> {
+		Ok(Content::I128(value))
+	}
+
+	fn visit_u8<E: Error>(self, value: u8) -> Result<Self::Value, E> {
+		Ok(Content::U8(value))
+	}
+
+	fn visit_u16<E: Error>(self, value: u16) -> Result<Self::Value, E> {
+		Ok(Content::U16(value))
+	}
+
+	fn visit_u32<E: Error>(self, value: u32) -> Result<Self::Value, E> {
+		Ok(Content::U32(value))
+	}
+
+	fn visit_u64<E: Error>(self, value: u64) -> Result<Self::Value, E> {
+		Ok(Content::U64(value))
+	}
+
+	fn visit_u128<E: Error>(self, value: u128) -> Result<Self::Value, E> {
+		Ok(Content::U128(value))
+	}
+
+	fn visit_f32<E: Error>(self, value: f32) -> Result<Self::Value, E> {
+		Ok(Content::F32(value))
+	}
+
+	fn visit_f64<E: Error>(self, value: f64) -> Result<Self::Value, E> {
+		Ok(Content::F64(value))
+	}
+
+	fn visit_char<E: Error>(self, value: char) -> Result<Self::Value, E> {
+		Ok(Content::Char(value))
+	}
+
+	fn visit_str<E: Error>(self, value: &str) -> Result<Self::Value, E> {
+		Ok(Content::String(value.into()))
+	}
+
+	fn visit_borrowed_str<E: Error>(self, value: &'de str) -> Result<Self::Value, E> {
+		Ok(Content::Str(value))
+	}
+
+	fn visit_string<E: Error>(self, value: String) -> Result<Self::Value, E> {
+		Ok(Content::String(value))
+	}
+
+	fn visit_bytes<E: Error>(self, value: &[u8]) -> Result<Self::Value, E> {
+		Ok(Content::ByteBuf(value.into()))
+	}
+
+	fn visit_borrowed_bytes<E: Error>(self, value: &'de [u8]) -> Result<Self::Value, E> {
+		Ok(Content::Bytes(value))
+	}
+
+	fn visit_byte_buf<E: Error>(self, value: Vec<u8>) -> Result<Self::Value, E> {
+		Ok(Content::ByteBuf(value))
+	}
+
+	fn visit_none<E: Error>(self) -> Result<Self::Value, E> {
+		Ok(Content::None)
+	}
+
+	fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+		let value = Content::deserialize(deserializer)?;
+		Ok(Content::Some(Box::new(value)))
+	}
+
+	fn visit_unit<E: Error>(self) -> Result<Self::Value, E> {
+		Ok(Content::Unit)
+	}
+
+	fn visit_newtype_struct<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+		let value = Content::deserialize(deserializer)?;
+		Ok(Content::Newtype(Box::new(value)))
+	}
+
+	fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+		let mut items = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+		while let Some(item) = seq.next_element()? {
+			items.push(item);
+		}
+		Ok(Content::Seq(items))
+	}
+
+	fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+		let mut entries = Vec::with_capacity(map.size_hint().unwrap_or(0).min(4096));
+		while let Some(entry) = map.next_entry()? {
+			entries.push(entry);
+		}
+		Ok(Content::Map(entries))
+	}
+
+	fn visit_enum<A: serde::de::EnumAccess<'de>>(self, _data: A) -> Result<Self::Value, A::Error> {
+		Err(A::Error::custom(
+			"double tagged enums can not buffer enum values that appear before the tag field",
+		))
+	}
+}
+
+/// A deserializer that reads from buffered [`Content`].
+pub struct ContentDeserializer<'de, E> {
+	content: Content<'de>,
+	_error: core::marker::PhantomData<fn() -> E>,
+}
+
+impl<'de, E> ContentDeserializer<'de, E> {
+	/// Create a new deserializer for the given content.
+	pub fn new(content: Content<'de>) -> Self {
+		Self {
+			content,
+			_error: core::marker::PhantomData,
+		}
+	}
+}
+
+impl<'de, E: Error> IntoDeserializer<'de, E> for Content<'de> {
+	type Deserializer = ContentDeserializer<'de, E>;
+
+	fn into_deserializer(self) -> Self::Deserializer {
+		ContentDeserializer::new(self)
+	}
+}
+
+impl<'de, E: Error> Deserializer<'de> for ContentDeserializer<'de, E> {
+	type Error = E;
+
+	serde::forward_to_deserialize_any! {
+		bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+		bytes byte_buf unit unit_struct seq tuple
+		tuple_struct map struct identifier
+	}
+
+	fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		match self.content {
+			Content::Bool(x) => visitor.visit_bool(x),
+			Content::U8(x) => visitor.visit_u8(x),
+			Content::U16(x) => visitor.visit_u16(x),
+			Content::U32(x) => visitor.visit_u32(x),
+			Content::U64(x) => visitor.visit_u64(x),
+			Content::U128(x) => visitor.visit_u128(x),
+			Content::I8(x) => visitor.visit_i8(x),
+			Content::I16(x) => visitor.visit_i16(x),
+			Content::I32(x) => visitor.visit_i32(x),
+			Content::I64(x) => visitor.visit_i64(x),
+			Content::I128(x) => visitor.visit_i128(x),
+			Content::F32(x) => visitor.visit_f32(x),
+			Content::F64(x) => visitor.visit_f64(x),
+			Content::Char(x) => visitor.visit_char(x),
+			Content::String(x) => visitor.visit_string(x),
+			Content::Str(x) => visitor.visit_borrowed_str(x),
+			Content::ByteBuf(x) => visitor.visit_byte_buf(x),
+			Content::Bytes(x) => visitor.visit_borrowed_bytes(x),
+			Content::None => visitor.visit_none(),
+			Content::Some(x) => visitor.visit_some(ContentDeserializer::new(*x)),
+			Content::Unit => visitor.visit_unit(),
+			Content::Newtype(x) => visitor.visit_newtype_struct(ContentDeserializer::new(*x)),
+			Content::Seq(x) => {
+				let mut seq = serde::de::value::SeqDeserializer::new(x.into_iter());
+				let value = visitor.visit_seq(&mut seq)?;
+				seq.end()?;
+				Ok(value)
+			},
+			Content::Map(x) => {
+				let mut map = serde::de::value::MapDeserializer::new(x.into_iter());
+				let value = visitor.visit_map(&mut map)?;
+				map.end()?;
+				Ok(value)
+			},
+		}
+	}
+
+	fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		match self.content {
+			Content::None | Content::Unit => visitor.visit_none(),
+			Content::Some(x) => visitor.visit_some(ContentDeserializer::new(*x)),
+			content => visitor.visit_some(ContentDeserializer::new(content)),
+		}
+	}
+
+	fn deserialize_newtype_struct<V: Visitor<'de>>(
+		self,
+		_name: &'static str,
+		visitor: V,
+	) -> Result<V::Value, Self::Error> {
+		match self.content {
+			Content::Newtype(x) => visitor.visit_newtype_struct(ContentDeserializer::new(*x)),
+			content => visitor.visit_newtype_struct(ContentDeserializer::new(content)),
+		}
+	}
+
+	fn deserialize_enum<V: Visitor<'de>>(
+		self,
+		_name: &'static str,
+		_variants: &'static [&'static str],
+		visitor: V,
+	) -> Result<V::Value, Self::Error> {
+		let (variant, value) = match self.content {
+			Content::Map(entries) => {
+				let mut entries = entries.into_iter();
+				let entry = match (entries.next(), entries.next()) {
+					(Some(entry), None) => entry,
+					_ => return Err(E::invalid_value(serde::de::Unexpected::Map, &"map with a single key")),
+				};
+				(entry.0, Some(entry.1))
+			},
+			content @ (Content::String(_) | Content::Str(_)) => (content, None),
+			other => return Err(E::invalid_type(other.unexpected(), &"string or map")),
+		};
+		visitor.visit_enum(EnumDeserializer {
+			variant,
+			value,
+			_error: core::marker::PhantomData,
+		})
+	}
+
+	fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		drop(self);
+		visitor.visit_unit()
+	}
+}
+
+struct EnumDeserializer<'de, E> {
+	variant: Content<'de>,
+	value: Option<Content<'de>>,
+	_error: core::marker::PhantomData<fn() -> E>,
+}
+
+impl<'de, E: Error> serde::de::EnumAccess<'de> for EnumDeserializer<'de, E> {
+	type Error = E;
+	type Variant = VariantDeserializer<'de, E>;
+
+	fn variant_seed<V: serde::de::DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self::Variant), E> {
+		let variant = seed.deserialize(ContentDeserializer::new(self.variant))?;
+		let visitor = VariantDeserializer {
+			value: self.value,
+			_error: core::marker::PhantomData,
+		};
+		Ok((variant, visitor))
+	}
+}
+
+struct VariantDeserializer<'de, E> {
+	value: Option<Content<'de>>,
+	_error: core::marker::PhantomData<fn() -> E>,
+}
+
+impl<'de, E: Error> serde::de::VariantAccess<'de> for VariantDeserializer<'de, E> {
+	type Error = E;
+
+	fn unit_variant(self) -> Result<(), E> {
+		match self.value {
+			None => Ok(()),
+			Some(value) => Deserialize::deserialize(ContentDeserializer::new(value)),
+		}
+	}
+
+	fn newtype_variant_seed<T: serde::de::DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, E> {
+		match self.value {
+			Some(value) => seed.deserialize(ContentDeserializer::new(value)),
+			None => Err(E::invalid_type(serde::de::Unexpected::UnitVariant, &"newtype variant")),
+		}
+	}
+
+	fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, E> {
+		match self.value {
+			Some(value @ Content::Seq(_)) => ContentDeserializer::new(value).deserialize_any(visitor),
+			Some(other) => Err(E::invalid_type(other.unexpected(), &"tuple variant")),
+			None => Err(E::invalid_type(serde::de::Unexpected::UnitVariant, &"tuple variant")),
+		}
+	}
+
+	fn struct_variant<V: Visitor<'de>>(self, _fields: &'static [&'static str], visitor: V) -> Result<V::Value, E> {
+		match self.value {
+			Some(value @ (Content::Map(_) | Content::Seq(_))) => {
+				ContentDeserializer::new(value).deserialize_any(visitor)
+			},
+			Some(other) => Err(E::invalid_type(other.unexpected(), &"struct variant")),
+			None => Err(E::invalid_type(serde::de::Unexpected::UnitVariant, &"struct variant")),
+		}
+	}
+}
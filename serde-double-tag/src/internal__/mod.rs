@@ -0,0 +1,1144 @@
+//! Internal utilities for use by the derive macros.
+//!
+//! Do not call these directly, as they may be changed without matching semver bump.
+
+#![deny(missing_docs)]
+
+use std::borrow::Cow;
+
+pub use ::serde;
+
+pub use crate::{DoubleTagged, MissingContent, ParseTagError, VariantInfo};
+
+mod content;
+pub use content::{Content, ContentDeserializer};
+
+mod tag;
+pub use tag::{parse_tag_value, DeserializedTag, TagValue, UnknownTag};
+
+#[cfg(feature = "schemars")]
+pub use ::schemars;
+
+/// Convert a value into a [`String`].
+pub fn string(input: impl Into<String>) -> String {
+	input.into()
+}
+
+/// Convert a value into a [`serde_json::Value`].
+#[inline]
+#[cfg(feature = "schemars")]
+pub fn json_value(input: impl Into<serde_json::Value>) -> serde_json::Value {
+	input.into()
+}
+
+/// The fields that may appear in an object next to the given properties.
+#[derive(Copy, Clone)]
+#[cfg(feature = "schemars")]
+pub struct OtherFields<'a> {
+	/// The content field names of inactive variants.
+	pub inactive_content: &'a [&'a str],
+
+	/// Reject fields that are not a property or inactive content.
+	pub deny_unknown_fields: bool,
+
+	/// Reject content fields of inactive variants.
+	pub deny_inactive_content: bool,
+
+	/// Accept `null` for content fields of inactive variants, even if they are rejected otherwise.
+	pub null_inactive: bool,
+}
+
+#[cfg(feature = "schemars")]
+impl OtherFields<'_> {
+	/// Allow any other field.
+	pub const ALLOW: Self = Self {
+		inactive_content: &[],
+		deny_unknown_fields: false,
+		deny_inactive_content: false,
+		null_inactive: false,
+	};
+}
+
+/// Create a schema for an object with the given properties.
+///
+/// All properties will be required.
+/// If unknown fields are denied, `additionalProperties` is set to `false`.
+/// The content fields of inactive variants are added as optional properties
+/// that accept any value or no value at all, depending on `other_fields.deny_inactive_content`.
+/// If `other_fields.null_inactive` is true, denied content fields still accept `null`.
+#[inline]
+#[cfg(feature = "schemars")]
+pub fn object_schema(
+	mut properties: schemars::Map<String, schemars::schema::Schema>,
+	other_fields: OtherFields,
+) -> schemars::schema::Schema {
+	let required = properties.keys().cloned().collect();
+
+	// Only add the inactive content fields if it makes a difference for the additional properties.
+	let inactive_schema = match (other_fields.deny_inactive_content, other_fields.null_inactive) {
+		(true, true) => Some(unit_schema()),
+		(deny, _) if deny != other_fields.deny_unknown_fields => Some(schemars::schema::Schema::Bool(!deny)),
+		_ => None,
+	};
+	if let Some(inactive_schema) = inactive_schema {
+		for name in other_fields.inactive_content {
+			if !properties.contains_key(*name) {
+				properties.insert(name.to_string(), inactive_schema.clone());
+			}
+		}
+	}
+
+	let additional_properties = if other_fields.deny_unknown_fields {
+		Some(Box::new(schemars::schema::Schema::Bool(false)))
+	} else {
+		None
+	};
+
+	schemars::schema::SchemaObject {
+		instance_type: Some(schemars::schema::SingleOrVec::Single(Box::new(
+			schemars::schema::InstanceType::Object,
+		))),
+		object: Some(Box::new(schemars::schema::ObjectValidation {
+			properties,
+			required,
+			additional_properties,
+			..Default::default()
+		})),
+		..Default::default()
+	}
+	.into()
+}
+
+/// Create a schema for a constant tag value.
+#[inline]
+#[cfg(feature = "schemars")]
+pub fn const_value(value: TagValue) -> schemars::schema::Schema {
+	schemars::schema::SchemaObject {
+		instance_type: Some(schemars::schema::SingleOrVec::Single(Box::new(value.instance_type()))),
+		const_value: Some(value.to_json()),
+		..Default::default()
+	}
+	.into()
+}
+
+/// Create a schema for a tag value that must be one of the given values.
+///
+/// If `open` is true, any value with the same type as one of the given values is accepted.
+#[inline]
+#[cfg(feature = "schemars")]
+pub fn enum_value(values: &[TagValue], open: bool) -> schemars::schema::Schema {
+	let mut instance_types: Vec<_> = Vec::with_capacity(1);
+	for value in values {
+		let instance_type = value.instance_type();
+		if !instance_types.contains(&instance_type) {
+			instance_types.push(instance_type);
+		}
+	}
+	let instance_type = match instance_types.len() {
+		0 if open => Some(schemars::schema::InstanceType::String.into()),
+		0 => None,
+		1 => Some(instance_types.remove(0).into()),
+		_ => Some(instance_types.into()),
+	};
+	let enum_values = match open {
+		true => None,
+		false => Some(values.iter().map(|x| x.to_json()).collect()),
+	};
+	schemars::schema::SchemaObject {
+		instance_type,
+		enum_values,
+		..Default::default()
+	}
+	.into()
+}
+
+/// Create a subschema for a variant.
+///
+/// The tag aliases are accepted as alternative tag values,
+/// and the content aliases as alternative content field names.
+/// If `content_required` is false, the content field may also be left out.
+#[inline]
+#[cfg(feature = "schemars")]
+#[allow(clippy::too_many_arguments)]
+pub fn variant_subschema(
+	tag_field_name: &'static str,
+	tag_value: TagValue,
+	tag_aliases: &[TagValue],
+	content_field_name: &str,
+	content_aliases: &[&str],
+	variant_subschema: schemars::schema::Schema,
+	content_required: bool,
+	other_fields: OtherFields,
+) -> schemars::schema::SubschemaValidation {
+	if tag_aliases.is_empty() && content_aliases.is_empty() {
+		let mut if_properties = schemars::Map::with_capacity(1);
+		if_properties.insert(tag_field_name.into(), const_value(tag_value));
+
+		let mut then_properties = schemars::Map::with_capacity(2);
+		then_properties.insert(tag_field_name.to_string(), const_value(tag_value));
+		then_properties.insert(content_field_name.to_string(), variant_subschema);
+		let mut then_schema = object_schema(then_properties, other_fields).into_object();
+		if !content_required {
+			then_schema.object().required.remove(content_field_name);
+		}
+
+		return schemars::schema::SubschemaValidation {
+			if_schema: Some(Box::new(object_schema(if_properties, OtherFields::ALLOW))),
+			then_schema: Some(Box::new(then_schema.into())),
+			..Default::default()
+		};
+	}
+
+	let mut tag_values = Vec::with_capacity(tag_aliases.len() + 1);
+	tag_values.push(tag_value);
+	tag_values.extend_from_slice(tag_aliases);
+
+	let mut content_field_names = Vec::with_capacity(content_aliases.len() + 1);
+	content_field_names.push(content_field_name);
+	content_field_names.extend_from_slice(content_aliases);
+
+	let mut if_properties = schemars::Map::with_capacity(1);
+	if_properties.insert(tag_field_name.into(), enum_value(&tag_values, false));
+
+	// Require the tag field and at least one of the content fields.
+	let mut then_properties = schemars::Map::with_capacity(1 + content_field_names.len());
+	then_properties.insert(tag_field_name.to_string(), enum_value(&tag_values, false));
+	let mut any_of = Vec::with_capacity(content_field_names.len());
+	for name in &content_field_names {
+		then_properties.insert(name.to_string(), variant_subschema.clone());
+		any_of.push(object_schema(
+			schemars::Map::from_iter([(name.to_string(), true.into())]),
+			OtherFields::ALLOW,
+		));
+	}
+	let mut then_schema = object_schema(then_properties, other_fields).into_object();
+	then_schema.object().required = [tag_field_name.to_string()].into();
+	if content_required {
+		then_schema.subschemas().any_of = Some(any_of);
+	}
+
+	schemars::schema::SubschemaValidation {
+		if_schema: Some(Box::new(object_schema(if_properties, OtherFields::ALLOW))),
+		then_schema: Some(Box::new(then_schema.into())),
+		..Default::default()
+	}
+}
+
+/// Create a schema with the given subschema.
+#[inline]
+#[cfg(feature = "schemars")]
+pub fn subschema_to_schema(subschema: schemars::schema::SubschemaValidation) -> schemars::schema::Schema {
+	schemars::schema::SchemaObject {
+		subschemas: Some(Box::new(subschema)),
+		..Default::default()
+	}
+	.into()
+}
+
+/// Adjust the schema of an enum for use in a struct with `#[serde(flatten)]`.
+///
+/// The fields of the parent struct appear next to the tag and content fields,
+/// so the variant subschemas must not reject unknown fields.
+#[cfg(feature = "schemars")]
+pub fn flatten_schema(schema: schemars::schema::Schema) -> schemars::schema::Schema {
+	let schemars::schema::Schema::Object(mut schema) = schema else {
+		return schema;
+	};
+	if let Some(object) = &mut schema.object {
+		object.additional_properties = None;
+	}
+	if let Some(subschemas) = &mut schema.subschemas {
+		if let Some(all_of) = subschemas.all_of.take() {
+			subschemas.all_of = Some(all_of.into_iter().map(flatten_schema).collect());
+		}
+		if let Some(then_schema) = subschemas.then_schema.take() {
+			subschemas.then_schema = Some(Box::new(flatten_schema(*then_schema)));
+		}
+	}
+	schema.into()
+}
+
+/// Create a schema for a unit value.
+#[inline]
+#[cfg(feature = "schemars")]
+pub fn unit_schema() -> schemars::schema::Schema {
+	schemars::schema::SchemaObject {
+		instance_type: Some(schemars::schema::SingleOrVec::Single(Box::new(
+			schemars::schema::InstanceType::Null,
+		))),
+		..Default::default()
+	}
+	.into()
+}
+
+/// Create a schema for an empty map.
+#[inline]
+#[cfg(feature = "schemars")]
+pub fn empty_map_schema() -> schemars::schema::Schema {
+	object_schema(schemars::Map::new(), OtherFields {
+		deny_unknown_fields: true,
+		..OtherFields::ALLOW
+	})
+}
+
+/// Add a default value to a schema.
+///
+/// The schema is returned unchanged if there is no default value,
+/// which happens if the default value can not be serialized.
+#[inline]
+#[cfg(feature = "schemars")]
+pub fn with_default(schema: schemars::schema::Schema, default: Option<serde_json::Value>) -> schemars::schema::Schema {
+	match default {
+		None => schema,
+		Some(default) => {
+			let mut schema = schema.into_object();
+			schema.metadata().default = Some(default);
+			schema.into()
+		},
+	}
+}
+
+/// Create a subschema for a unit variant with `#[serde(unit_content = "omit")]`.
+///
+/// The tag aliases are accepted as alternative tag values.
+/// The content field and its aliases must not be present.
+#[inline]
+#[cfg(feature = "schemars")]
+pub fn omitted_variant_subschema(
+	tag_field_name: &'static str,
+	tag_value: TagValue,
+	tag_aliases: &[TagValue],
+	content_field_names: &[&str],
+	other_fields: OtherFields,
+) -> schemars::schema::SubschemaValidation {
+	let mut tag_values = Vec::with_capacity(tag_aliases.len() + 1);
+	tag_values.push(tag_value);
+	tag_values.extend_from_slice(tag_aliases);
+	let tag_schema = match tag_values.len() {
+		1 => const_value(tag_value),
+		_ => enum_value(&tag_values, false),
+	};
+
+	let mut if_properties = schemars::Map::with_capacity(1);
+	if_properties.insert(tag_field_name.into(), tag_schema.clone());
+
+	let mut then_properties = schemars::Map::with_capacity(1 + content_field_names.len());
+	then_properties.insert(tag_field_name.to_string(), tag_schema);
+	let mut then_schema = object_schema(then_properties, other_fields).into_object();
+	for name in content_field_names {
+		then_schema.object().properties.insert(name.to_string(), false.into());
+	}
+
+	schemars::schema::SubschemaValidation {
+		if_schema: Some(Box::new(object_schema(if_properties, OtherFields::ALLOW))),
+		then_schema: Some(Box::new(then_schema.into())),
+		..Default::default()
+	}
+}
+
+/// Names of the tag and content fields for an enum variant.
+#[repr(C)]
+pub struct FieldNames {
+	/// The name of the tag field.
+	pub tag: &'static str,
+
+	/// The name of the content field for this variant.
+	pub content: &'static str,
+
+	/// Alternative names for the content field.
+	pub content_aliases: &'static [&'static str],
+}
+
+impl FieldNames {
+	/// Get the names of the tag and content field as a slice.
+	fn as_slice(&'static self) -> &'static [&'static str] {
+		unsafe { core::slice::from_raw_parts(&self.tag, 2) }
+	}
+}
+
+/// Content fields that were encountered before the tag field.
+///
+/// We can not deserialize these until we know which variant is active,
+/// so they are buffered until the tag field is found.
+pub struct Buffered<'de> {
+	entries: Vec<(Cow<'static, str>, Content<'de>)>,
+}
+
+impl<'de> Buffered<'de> {
+	/// Create an empty buffer.
+	fn new() -> Self {
+		Self { entries: Vec::new() }
+	}
+
+	/// Add a field to the buffer.
+	fn insert<E: serde::de::Error>(&mut self, field_name: Cow<'static, str>, content: Content<'de>) -> Result<(), E> {
+		if self.entries.iter().any(|(name, _)| *name == field_name) {
+			return Err(match field_name {
+				Cow::Borrowed(name) => E::duplicate_field(name),
+				Cow::Owned(name) => E::custom(format_args!("duplicate field `{name}`")),
+			});
+		}
+		self.entries.push((field_name, content));
+		Ok(())
+	}
+
+	/// Take the buffered content for a content field or one of its aliases, if it was encountered.
+	///
+	/// Returns an error if more than one of the names was encountered.
+	fn take<E: serde::de::Error>(&mut self, field_name: &str, aliases: &[&str]) -> Result<Option<Content<'de>>, E> {
+		let matches = |name: &Cow<'static, str>| *name == field_name || aliases.contains(&name.as_ref());
+		let Some(index) = self.entries.iter().position(|(name, _)| matches(name)) else {
+			return Ok(None);
+		};
+		if self.entries[index + 1..].iter().any(|(name, _)| matches(name)) {
+			return Err(E::custom(format_args!("duplicate field `{field_name}`")));
+		}
+		Ok(Some(self.entries.swap_remove(index).1))
+	}
+}
+
+/// Information about an enum needed to deserialize the tag field.
+pub struct EnumInfo<Tag: 'static> {
+	/// The name of the tag field.
+	pub tag_field_name: &'static str,
+
+	/// The tag values of all variants, including aliases.
+	pub tag_values: &'static [(TagValue, Tag)],
+
+	/// What to do with tag values that do not match any variant.
+	pub unknown_tag: UnknownTag<Tag>,
+
+	/// The content field names of all variants, including aliases.
+	pub content_fields: &'static [(&'static str, Tag)],
+
+	/// Buffer unknown fields encountered before the tag, instead of ignoring them.
+	pub buffer_unknown_fields: bool,
+
+	/// Infer the variant from the content field if the tag field is missing.
+	pub infer_tag: bool,
+
+	/// Reject fields that are not the tag or a content field.
+	pub deny_unknown_fields: bool,
+
+	/// Reject content fields of inactive variants.
+	pub deny_inactive_content: bool,
+
+	/// Accept and ignore `null` values for the content fields of inactive variants.
+	///
+	/// These are accepted even if [`Self::deny_inactive_content`] is true, and they are not validated.
+	pub null_inactive: bool,
+}
+
+/// The content fields of a double tagged enum, used by [`crate::Preserving`].
+///
+/// Implemented by the `Deserialize` derive macro.
+pub trait ContentFields {
+	/// The content field names of all variants, including aliases, with the index of the variant.
+	const CONTENT_FIELDS: &'static [(&'static str, usize)];
+
+	/// Get the index of the active variant.
+	///
+	/// Returns `None` for the `#[serde(catch_all)]` variant, since it has no known content field.
+	fn variant_index(&self) -> Option<usize>;
+
+	/// Get the content fields that are serialized as `null` for the active variant, with `#[serde(null_inactive)]`.
+	fn null_content_fields(&self) -> &'static [&'static str];
+}
+
+/// Function to validate the content of an inactive variant.
+pub type Validate<'a, 'de, Tag, E> = &'a dyn Fn(Tag, Content<'de>) -> Result<(), E>;
+
+/// The remaining fields of a map after the tag field has been deserialized.
+pub struct RemainingFields<'a, 'de, Tag: 'static, M: serde::de::MapAccess<'de>> {
+	info: &'static EnumInfo<Tag>,
+	buffered: Buffered<'de>,
+	map: M,
+	validate: Option<Validate<'a, 'de, Tag, M::Error>>,
+	lenient: bool,
+}
+
+/// Deserialize the tag field from a `MapAccess`.
+///
+/// The tag value is looked up in the tag values of the enum to find the matching variant.
+/// Tag values that do not match any variant are handled according to [`EnumInfo::unknown_tag`].
+///
+/// If the tag field is not the first field in the map,
+/// the values of all content fields encountered before the tag are buffered.
+/// Unknown fields before the tag are ignored,
+/// unless [`EnumInfo::buffer_unknown_fields`] or [`EnumInfo::deny_unknown_fields`] is true.
+///
+/// If the tag field is missing and [`EnumInfo::infer_tag`] is true,
+/// the variant is inferred from the buffered content fields.
+/// This only succeeds if the content fields belong to exactly one variant.
+///
+/// If `validate` is given, it is called for the content of all inactive variants
+/// when the remaining fields are deserialized.
+#[allow(clippy::type_complexity)]
+pub fn deserialize_tag<'a, 'de, Tag, M>(
+	info: &'static EnumInfo<Tag>,
+	mut map: M,
+	validate: Option<Validate<'a, 'de, Tag, M::Error>>,
+) -> Result<(DeserializedTag<'de, Tag>, RemainingFields<'a, 'de, Tag, M>), M::Error>
+where
+	Tag: Copy + PartialEq + 'static,
+	M: serde::de::MapAccess<'de>,
+{
+	let mut buffered = Buffered::new();
+	let key_seed = KeySeed {
+		tag_field_name: info.tag_field_name,
+		content_fields: info.content_fields,
+		buffer_unknown_fields: info.buffer_unknown_fields || info.deny_unknown_fields,
+	};
+	let tag = loop {
+		match map.next_key_seed(key_seed)? {
+			None if info.infer_tag => break DeserializedTag::Known(infer_tag(info, &buffered)?),
+			None => return Err(serde::de::Error::missing_field(info.tag_field_name)),
+			Some(Key::Tag) => {
+				break map.next_value_seed(tag::TagSeed {
+					values: info.tag_values,
+					unknown: info.unknown_tag,
+				})?;
+			},
+			Some(Key::Content(name)) => {
+				buffered.insert(Cow::Borrowed(name), map.next_value()?)?;
+			},
+			Some(Key::Unknown(name)) => {
+				buffered.insert(Cow::Owned(name), map.next_value()?)?;
+			},
+			Some(Key::Other) => {
+				let _: serde::de::IgnoredAny = map.next_value()?;
+			},
+		}
+	};
+	let remaining = RemainingFields {
+		info,
+		buffered,
+		map,
+		validate,
+		lenient: false,
+	};
+	Ok((tag, remaining))
+}
+
+/// Prepare to deserialize the fields of a specific variant from a `MapAccess`, ignoring the tag field.
+///
+/// The tag field, unknown fields and the content of other variants are all ignored,
+/// regardless of [`EnumInfo::deny_unknown_fields`] and [`EnumInfo::deny_inactive_content`].
+pub fn ignore_tag<'a, 'de, Tag, M>(info: &'static EnumInfo<Tag>, map: M) -> RemainingFields<'a, 'de, Tag, M>
+where
+	Tag: Copy + 'static,
+	M: serde::de::MapAccess<'de>,
+{
+	RemainingFields {
+		info,
+		buffered: Buffered::new(),
+		map,
+		validate: None,
+		lenient: true,
+	}
+}
+
+/// Deserialize a tag value on its own.
+///
+/// Tag values that do not match any variant are mapped to `other` if it is given, or produce an error otherwise.
+pub fn deserialize_tag_value<'de, D, Tag>(
+	deserializer: D,
+	values: &'static [(TagValue, Tag)],
+	other: Option<Tag>,
+) -> Result<Tag, D::Error>
+where
+	D: serde::Deserializer<'de>,
+	Tag: Copy + 'static,
+{
+	use serde::de::DeserializeSeed;
+	let unknown = match other {
+		Some(tag) => UnknownTag::Variant(tag),
+		None => UnknownTag::Error,
+	};
+	match (tag::TagSeed { values, unknown }).deserialize(deserializer)? {
+		DeserializedTag::Known(tag) => Ok(tag),
+		DeserializedTag::Unknown(_) => unreachable!(),
+	}
+}
+
+/// Infer the variant from the buffered content fields when the tag field is missing.
+fn infer_tag<Tag, E>(info: &'static EnumInfo<Tag>, buffered: &Buffered<'_>) -> Result<Tag, E>
+where
+	Tag: Copy + PartialEq + 'static,
+	E: serde::de::Error,
+{
+	let mut found: Option<(&str, Tag)> = None;
+	for (name, content) in &buffered.entries {
+		let Some(&(_, tag)) = info.content_fields.iter().find(|(x, _)| x == name) else {
+			continue;
+		};
+		if info.null_inactive && content.is_null() {
+			continue;
+		}
+		match found {
+			None => found = Some((name, tag)),
+			Some((_, found_tag)) if found_tag == tag => (),
+			Some((found_name, _)) => {
+				return Err(E::custom(format_args!(
+					"missing field `{}` and can not infer the variant: found content fields for multiple variants: `{}` and `{}`",
+					info.tag_field_name, found_name, name,
+				)));
+			},
+		}
+	}
+	match found {
+		Some((_, tag)) => Ok(tag),
+		None => Err(E::missing_field(info.tag_field_name)),
+	}
+}
+
+/// Deserialize the variant fields from a `MapAccess`.
+pub fn deserialize_variant_required<'de, Tag, T, M>(
+	fields: &'static FieldNames,
+	remaining: RemainingFields<'_, 'de, Tag, M>,
+) -> Result<T, M::Error>
+where
+	Tag: Copy + 'static,
+	T: serde::de::Deserialize<'de>,
+	M: serde::de::MapAccess<'de>,
+{
+	match deserialize_variant(fields, remaining)? {
+		Some(variant) => Ok(variant),
+		None => Err(serde::de::Error::missing_field(fields.content)),
+	}
+}
+
+/// Deserialize the fields of a variant, substituting the default value if the field is not present.
+pub fn deserialize_variant_optional<'de, Tag, T, M>(
+	fields: &'static FieldNames,
+	remaining: RemainingFields<'_, 'de, Tag, M>,
+) -> Result<T, M::Error>
+where
+	Tag: Copy + 'static,
+	T: serde::de::Deserialize<'de> + Default,
+	M: serde::de::MapAccess<'de>,
+{
+	let variant = deserialize_variant(fields, remaining)?;
+	Ok(variant.unwrap_or_default())
+}
+
+/// How the content of unit variants is encoded, set with `#[serde(unit_content = "...")]`.
+///
+/// Without the attribute, the content is [`UnitContent::Optional`].
+#[derive(Copy, Clone)]
+pub enum UnitContent {
+	/// The content field may be omitted or `null`.
+	Optional,
+
+	/// The content field must be omitted.
+	Omit,
+
+	/// The content field must be `null`.
+	Null,
+
+	/// The content field must be an empty map.
+	Empty,
+
+	/// The content field may be omitted, `null`, an empty sequence or an empty map.
+	///
+	/// Used for tuple and struct variants without fields with `#[serde(empty_as_unit)]`,
+	/// so that content written before enabling the attribute can still be read.
+	AnyEmpty,
+}
+
+/// Deserialize the content of a unit variant with the given encoding.
+pub fn deserialize_variant_unit<'de, Tag, M>(
+	fields: &'static FieldNames,
+	remaining: RemainingFields<'_, 'de, Tag, M>,
+	encoding: UnitContent,
+) -> Result<(), M::Error>
+where
+	Tag: Copy + 'static,
+	M: serde::de::MapAccess<'de>,
+{
+	match encoding {
+		UnitContent::Optional => deserialize_variant_optional::<_, (), _>(fields, remaining),
+		UnitContent::Omit => match deserialize_variant::<_, serde::de::IgnoredAny, _>(fields, remaining)? {
+			Some(_) => Err(serde::de::Error::custom(format_args!(
+				"unexpected field `{}`: unit variants have no content",
+				fields.content
+			))),
+			None => Ok(()),
+		},
+		UnitContent::Null => deserialize_variant_required::<_, (), _>(fields, remaining),
+		UnitContent::Empty => deserialize_variant_required::<_, EmptyMap, _>(fields, remaining).map(|_| ()),
+		UnitContent::AnyEmpty => deserialize_variant_optional::<_, EmptyValue, _>(fields, remaining).map(|_| ()),
+	}
+}
+
+/// Deserialize the `#[serde(other)]` variant, ignoring all content.
+pub fn deserialize_variant_other<'de, Tag, M>(remaining: RemainingFields<'_, 'de, Tag, M>) -> Result<(), M::Error>
+where
+	Tag: Copy + 'static,
+	M: serde::de::MapAccess<'de>,
+{
+	remaining.finish(None, &[], false)
+}
+
+/// Deserialize the content of the `#[serde(catch_all)]` variant for an unrecognized tag.
+///
+/// The content is taken from the content field that matches the tag value (see [`content_field_name()`]).
+/// If that field is not present, the content is deserialized as a missing value,
+/// which succeeds for `Option<T>`.
+///
+/// All other fields are ignored, since we do not know anything about the variant.
+pub fn deserialize_variant_catch_all<'de, Tag, T, M>(
+	content_field_name: &str,
+	mut remaining: RemainingFields<'_, 'de, Tag, M>,
+) -> Result<T, M::Error>
+where
+	Tag: Copy + 'static,
+	T: serde::de::Deserialize<'de>,
+	M: serde::de::MapAccess<'de>,
+{
+	let content = remaining.deserialize_content(content_field_name, &[], &[], false)?;
+	remaining.finish(Some((content_field_name, &[])), &[], false)?;
+	match content {
+		Some(content) => Ok(content),
+		None => T::deserialize(MissingFieldDeserializer {
+			field_name: content_field_name,
+			_error: core::marker::PhantomData,
+		}),
+	}
+}
+
+/// Compute the content field name for a tag value that is only known at runtime.
+///
+/// If there is a content field name template, the `{tag}` placeholder is substituted with the tag value.
+/// Otherwise, the content field name is the same as the tag value.
+pub fn content_field_name<'a>(template: Option<&str>, tag: &'a str) -> Cow<'a, str> {
+	match template {
+		Some(template) => Cow::Owned(template.replace("{tag}", tag)),
+		None => Cow::Borrowed(tag),
+	}
+}
+
+/// Serialize the `#[serde(catch_all)]` variant with the captured tag and content.
+///
+/// If `content` is `None`, the content field is omitted.
+/// The fields in `null_fields` are written with a `null` value after the content.
+pub fn serialize_catch_all<S, T>(
+	serializer: S,
+	tag_field_name: &'static str,
+	tag: &str,
+	content_field_name: &str,
+	content: Option<&T>,
+	null_fields: &[&str],
+) -> Result<S::Ok, S::Error>
+where
+	S: serde::Serializer,
+	T: serde::Serialize + ?Sized,
+{
+	use serde::ser::SerializeMap;
+	let mut map = serializer.serialize_map(Some(1 + usize::from(content.is_some()) + null_fields.len()))?;
+	map.serialize_entry(tag_field_name, tag)?;
+	if let Some(content) = content {
+		map.serialize_entry(content_field_name, content)?;
+	}
+	for name in null_fields {
+		map.serialize_entry(name, &())?;
+	}
+	map.end()
+}
+
+/// Deserialize the fields of a variant from the buffered fields or the remainder of a `MapAccess`.
+///
+/// Returns `None` if the content field is not present.
+fn deserialize_variant<'de, Tag, T, M>(
+	fields: &'static FieldNames,
+	mut remaining: RemainingFields<'_, 'de, Tag, M>,
+) -> Result<Option<T>, M::Error>
+where
+	Tag: Copy + 'static,
+	T: serde::de::Deserialize<'de>,
+	M: serde::de::MapAccess<'de>,
+{
+	let known_fields = fields.as_slice();
+	let variant = remaining.deserialize_content(fields.content, fields.content_aliases, known_fields, true)?;
+	remaining.finish(Some((fields.content, fields.content_aliases)), known_fields, true)?;
+	Ok(variant)
+}
+
+impl<'de, Tag, M> RemainingFields<'_, 'de, Tag, M>
+where
+	Tag: Copy + 'static,
+	M: serde::de::MapAccess<'de>,
+{
+	/// Create a key seed for the remaining fields.
+	///
+	/// If `strict` is false or the tag field was ignored, unknown fields and content of inactive variants are ignored,
+	/// regardless of [`EnumInfo::deny_unknown_fields`] and [`EnumInfo::deny_inactive_content`].
+	fn key_seed<'a>(
+		&self,
+		active: Option<(&'a str, &'a [&'a str])>,
+		known_fields: &'static [&'static str],
+		strict: bool,
+	) -> VariantKeySeed<'a, Tag> {
+		let (field_name, aliases) = match active {
+			Some((field_name, aliases)) => (Some(field_name), aliases),
+			None => (None, &[][..]),
+		};
+		let strict = strict && !self.lenient;
+		VariantKeySeed {
+			tag_field_name: self.info.tag_field_name,
+			field_name,
+			aliases,
+			content_fields: self.info.content_fields,
+			known_fields,
+			deny_unknown_fields: strict && self.info.deny_unknown_fields,
+			deny_inactive_content: strict && self.info.deny_inactive_content,
+			null_inactive: self.info.null_inactive,
+		}
+	}
+
+	/// Deserialize a content field from the buffered fields, or from the `MapAccess`.
+	///
+	/// Fields in the `MapAccess` are consumed up to and including the content field or one of its aliases.
+	/// Returns `None` if the content field is not present.
+	fn deserialize_content<T>(
+		&mut self,
+		content_field_name: &str,
+		aliases: &[&str],
+		known_fields: &'static [&'static str],
+		strict: bool,
+	) -> Result<Option<T>, M::Error>
+	where
+		T: serde::de::Deserialize<'de>,
+	{
+		if let Some(content) = self.buffered.take(content_field_name, aliases)? {
+			return Ok(Some(content.deserialize_into()?));
+		}
+
+		let key_seed = self.key_seed(Some((content_field_name, aliases)), known_fields, strict);
+		loop {
+			match self.map.next_key_seed(key_seed)? {
+				None => return Ok(None),
+				Some(VariantKey::Active) => return Ok(Some(self.map.next_value()?)),
+				Some(VariantKey::Inactive(tag)) => self.skip_inactive(tag)?,
+				Some(VariantKey::InactiveNull(name)) => self.skip_inactive_null(name)?,
+				Some(VariantKey::Ignore) => {
+					let _: serde::de::IgnoredAny = self.map.next_value()?;
+				},
+			}
+		}
+	}
+
+	/// Skip the value of a content field of an inactive variant, validating it if requested.
+	///
+	/// If [`EnumInfo::null_inactive`] is true, `null` values are not validated.
+	fn skip_inactive(&mut self, tag: Tag) -> Result<(), M::Error> {
+		match self.validate {
+			Some(validate) => {
+				let content: Content = self.map.next_value()?;
+				if self.info.null_inactive && content.is_null() {
+					return Ok(());
+				}
+				validate(tag, content)
+			},
+			None => {
+				let _: serde::de::IgnoredAny = self.map.next_value()?;
+				Ok(())
+			},
+		}
+	}
+
+	/// Skip the value of a content field of an inactive variant, which must be `null`.
+	fn skip_inactive_null(&mut self, field_name: &str) -> Result<(), M::Error> {
+		match self.map.next_value::<Option<serde::de::IgnoredAny>>()? {
+			None => Ok(()),
+			Some(_) => Err(inactive_content_error(field_name)),
+		}
+	}
+
+	/// Process all remaining fields after the content of the active variant has been deserialized.
+	///
+	/// Content of inactive variants is validated if requested.
+	/// If `strict` is true, unknown fields and content of inactive variants are rejected
+	/// according to [`EnumInfo::deny_unknown_fields`] and [`EnumInfo::deny_inactive_content`].
+	/// A repeated content field of the active variant is rejected if unknown fields are denied.
+	fn finish(
+		mut self,
+		active: Option<(&str, &[&str])>,
+		known_fields: &'static [&'static str],
+		strict: bool,
+	) -> Result<(), M::Error> {
+		use serde::de::Error;
+
+		let key_seed = self.key_seed(active, known_fields, strict);
+		for (name, content) in std::mem::take(&mut self.buffered.entries) {
+			match self.info.content_fields.iter().find(|(x, _)| *x == name) {
+				Some(_) if self.info.null_inactive && content.is_null() => (),
+				Some(_) if key_seed.deny_inactive_content => return Err(inactive_content_error(&name)),
+				Some(&(_, tag)) => {
+					if let Some(validate) = self.validate {
+						validate(tag, content)?;
+					}
+				},
+				None if key_seed.deny_unknown_fields => return Err(M::Error::unknown_field(&name, known_fields)),
+				None => (),
+			}
+		}
+
+		while let Some(key) = self.map.next_key_seed(key_seed)? {
+			match key {
+				VariantKey::Active if key_seed.deny_unknown_fields => {
+					// The known fields are the tag and content field of the active variant.
+					return Err(M::Error::duplicate_field(
+						known_fields.last().copied().unwrap_or_default(),
+					));
+				},
+				VariantKey::Inactive(tag) => self.skip_inactive(tag)?,
+				VariantKey::InactiveNull(name) => self.skip_inactive_null(name)?,
+				VariantKey::Active | VariantKey::Ignore => {
+					let _: serde::de::IgnoredAny = self.map.next_value()?;
+				},
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Create the error for a content field of an inactive variant.
+fn inactive_content_error<E: serde::de::Error>(field_name: &str) -> E {
+	E::custom(format_args!(
+		"unexpected field `{field_name}`: content of an inactive variant"
+	))
+}
+
+/// A deserialized map key, classified by what field it refers to.
+enum Key {
+	/// The tag field.
+	Tag,
+
+	/// The content field of one of the variants.
+	Content(&'static str),
+
+	/// An unknown field that should be buffered.
+	Unknown(String),
+
+	/// Any other field.
+	Other,
+}
+
+/// A deserialize seed for map keys that appear before the tag field.
+struct KeySeed<Tag: 'static> {
+	tag_field_name: &'static str,
+	content_fields: &'static [(&'static str, Tag)],
+	buffer_unknown_fields: bool,
+}
+
+impl<Tag> Copy for KeySeed<Tag> {}
+
+impl<Tag> Clone for KeySeed<Tag> {
+	fn clone(&self) -> Self {
+		*self
+	}
+}
+
+impl<'de, Tag> serde::de::DeserializeSeed<'de> for KeySeed<Tag> {
+	type Value = Key;
+
+	fn deserialize<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+		deserializer.deserialize_identifier(self)
+	}
+}
+
+impl<'de, Tag> serde::de::Visitor<'de> for KeySeed<Tag> {
+	type Value = Key;
+
+	fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+		formatter.write_str("a field name")
+	}
+
+	fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Self::Value, E> {
+		if value == self.tag_field_name {
+			Ok(Key::Tag)
+		} else if let Some((name, _)) = self.content_fields.iter().find(|(x, _)| *x == value) {
+			Ok(Key::Content(name))
+		} else if self.buffer_unknown_fields {
+			Ok(Key::Unknown(value.into()))
+		} else {
+			Ok(Key::Other)
+		}
+	}
+}
+
+/// A map key after the tag field, classified by what field it refers to.
+enum VariantKey<Tag> {
+	/// The content field of the active variant.
+	Active,
+
+	/// The content field of an inactive variant.
+	Inactive(Tag),
+
+	/// The content field of an inactive variant, which is only accepted with a `null` value.
+	InactiveNull(&'static str),
+
+	/// A field that should be ignored.
+	Ignore,
+}
+
+/// A deserialize seed for map keys after the tag field.
+///
+/// Keys that match the field name or one of the aliases are the content field of the active variant.
+/// Without a field name, no key is considered active.
+///
+/// Unknown fields and content fields of inactive variants produce an error if they are denied.
+/// If `null_inactive` is true, denied content fields of inactive variants are still accepted with a `null` value.
+/// A repeated tag field is treated as unknown field.
+struct VariantKeySeed<'a, Tag: 'static> {
+	tag_field_name: &'static str,
+	field_name: Option<&'a str>,
+	aliases: &'a [&'a str],
+	content_fields: &'static [(&'static str, Tag)],
+	known_fields: &'static [&'static str],
+	deny_unknown_fields: bool,
+	deny_inactive_content: bool,
+	null_inactive: bool,
+}
+
+impl<Tag> Copy for VariantKeySeed<'_, Tag> {}
+
+impl<Tag> Clone for VariantKeySeed<'_, Tag> {
+	fn clone(&self) -> Self {
+		*self
+	}
+}
+
+impl<'de, Tag: Copy> serde::de::DeserializeSeed<'de> for VariantKeySeed<'_, Tag> {
+	type Value = VariantKey<Tag>;
+
+	fn deserialize<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+		deserializer.deserialize_identifier(self)
+	}
+}
+
+impl<'de, Tag: Copy> serde::de::Visitor<'de> for VariantKeySeed<'_, Tag> {
+	type Value = VariantKey<Tag>;
+
+	fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+		match self.field_name {
+			Some(field_name) => write!(formatter, "a field with name {field_name:?}"),
+			None => formatter.write_str("a field name"),
+		}
+	}
+
+	fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<Self::Value, E> {
+		if self.field_name == Some(value) || self.aliases.contains(&value) {
+			Ok(VariantKey::Active)
+		} else if value == self.tag_field_name {
+			match self.deny_unknown_fields {
+				true => Err(E::duplicate_field(self.tag_field_name)),
+				false => Ok(VariantKey::Ignore),
+			}
+		} else if let Some(&(name, tag)) = self.content_fields.iter().find(|(x, _)| *x == value) {
+			match (self.deny_inactive_content, self.null_inactive) {
+				(true, true) => Ok(VariantKey::InactiveNull(name)),
+				(true, false) => Err(inactive_content_error(value)),
+				(false, _) => Ok(VariantKey::Inactive(tag)),
+			}
+		} else {
+			match self.deny_unknown_fields {
+				true => Err(E::unknown_field(value, self.known_fields)),
+				false => Ok(VariantKey::Ignore),
+			}
+		}
+	}
+}
+
+/// An empty map, used for the content of unit variants with `#[serde(unit_content = "empty")]`.
+struct EmptyMap;
+
+impl<'de> serde::Deserialize<'de> for EmptyMap {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		deserializer.deserialize_map(EmptyMap)
+	}
+}
+
+impl<'de> serde::de::Visitor<'de> for EmptyMap {
+	type Value = EmptyMap;
+
+	fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+		formatter.write_str("an empty map")
+	}
+
+	fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+		match map.next_key::<serde::de::IgnoredAny>()? {
+			None => Ok(EmptyMap),
+			Some(_) => Err(serde::de::Error::invalid_length(1, &self)),
+		}
+	}
+}
+
+/// An empty value: `null`, an empty sequence or an empty map.
+///
+/// Used for the content of tuple and struct variants without fields with `#[serde(empty_as_unit)]`.
+#[derive(Default)]
+struct EmptyValue;
+
+impl<'de> serde::Deserialize<'de> for EmptyValue {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		deserializer.deserialize_any(EmptyValue)
+	}
+}
+
+impl<'de> serde::de::Visitor<'de> for EmptyValue {
+	type Value = EmptyValue;
+
+	fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+		formatter.write_str("null, an empty sequence or an empty map")
+	}
+
+	fn visit_unit<E: serde::de::Error>(self) -> Result<Self::Value, E> {
+		Ok(EmptyValue)
+	}
+
+	fn visit_none<E: serde::de::Error>(self) -> Result<Self::Value, E> {
+		Ok(EmptyValue)
+	}
+
+	fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+		match seq.next_element::<serde::de::IgnoredAny>()? {
+			None => Ok(EmptyValue),
+			Some(_) => Err(serde::de::Error::invalid_length(1, &self)),
+		}
+	}
+
+	fn visit_map<A: serde::de::MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+		match map.next_key::<serde::de::IgnoredAny>()? {
+			None => Ok(EmptyValue),
+			Some(_) => Err(serde::de::Error::invalid_length(1, &self)),
+		}
+	}
+}
+
+/// A deserializer for a missing field.
+///
+/// Deserializes as `None` for `Option<T>`, and produces a "missing field" error for anything else.
+struct MissingFieldDeserializer<'a, E> {
+	field_name: &'a str,
+	_error: core::marker::PhantomData<fn() -> E>,
+}
+
+impl<'de, E: serde::de::Error> serde::Deserializer<'de> for MissingFieldDeserializer<'_, E> {
+	type Error = E;
+
+	serde::forward_to_deserialize_any! {
+		bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+		bytes byte_buf unit unit_struct newtype_struct seq tuple
+		tuple_struct map struct enum identifier ignored_any
+	}
+
+	fn deserialize_any<V: serde::de::Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+		Err(E::custom(format_args!("missing field `{}`", self.field_name)))
+	}
+
+	fn deserialize_option<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+		visitor.visit_none()
+	}
+}
@@ -0,0 +1,240 @@
+use serde::de::{Error, Visitor};
+
+use super::Content;
+
+/// A value of the tag field.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TagValue {
+	/// A string tag value.
+	Str(&'static str),
+
+	/// An integer tag value.
+	///
+	/// The derive macro only accepts values in the range of `i64` and `u64`.
+	Int(i128),
+
+	/// A boolean tag value.
+	Bool(bool),
+}
+
+impl TagValue {
+	/// Convert the tag value to a JSON value.
+	#[cfg(feature = "schemars")]
+	pub fn to_json(self) -> serde_json::Value {
+		match self {
+			Self::Str(value) => value.into(),
+			Self::Int(value) => match i64::try_from(value) {
+				Ok(value) => value.into(),
+				Err(_) => u64::try_from(value).map(Into::into).unwrap_or(serde_json::Value::Null),
+			},
+			Self::Bool(value) => value.into(),
+		}
+	}
+
+	/// Get the JSON schema instance type of the tag value.
+	#[cfg(feature = "schemars")]
+	pub(crate) fn instance_type(self) -> schemars::schema::InstanceType {
+		match self {
+			Self::Str(_) => schemars::schema::InstanceType::String,
+			Self::Int(_) => schemars::schema::InstanceType::Integer,
+			Self::Bool(_) => schemars::schema::InstanceType::Boolean,
+		}
+	}
+}
+
+impl TagValue {
+	/// Check if a string is the textual representation of the tag value.
+	fn matches_str(self, input: &str) -> bool {
+		match self {
+			Self::Str(value) => value == input,
+			Self::Int(value) => input.parse() == Ok(value),
+			Self::Bool(value) => input.parse() == Ok(value),
+		}
+	}
+}
+
+impl serde::Serialize for TagValue {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		match *self {
+			Self::Str(value) => serializer.serialize_str(value),
+			Self::Int(value) => match i64::try_from(value) {
+				Ok(value) => serializer.serialize_i64(value),
+				Err(_) => match u64::try_from(value) {
+					Ok(value) => serializer.serialize_u64(value),
+					Err(_) => serializer.serialize_i128(value),
+				},
+			},
+			Self::Bool(value) => serializer.serialize_bool(value),
+		}
+	}
+}
+
+impl std::fmt::Display for TagValue {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Str(value) => f.write_str(value),
+			Self::Int(value) => value.fmt(f),
+			Self::Bool(value) => value.fmt(f),
+		}
+	}
+}
+
+/// What to do with tag values that do not match any variant.
+#[derive(Copy, Clone)]
+pub enum UnknownTag<Tag> {
+	/// Report an error.
+	Error,
+
+	/// Treat the tag as the given variant, used for the `#[serde(other)]` variant.
+	Variant(Tag),
+
+	/// Capture the tag value, used for the `#[serde(catch_all)]` variant.
+	Capture,
+}
+
+/// The result of deserializing the tag field.
+pub enum DeserializedTag<'de, Tag> {
+	/// The tag value matched a known variant.
+	Known(Tag),
+
+	/// The tag value did not match any variant and was captured.
+	///
+	/// This is only produced for [`UnknownTag::Capture`].
+	Unknown(Content<'de>),
+}
+
+/// A deserialize seed for the value of the tag field.
+#[derive(Copy, Clone)]
+pub(crate) struct TagSeed<Tag: 'static> {
+	pub values: &'static [(TagValue, Tag)],
+	pub unknown: UnknownTag<Tag>,
+}
+
+impl<Tag: Copy> TagSeed<Tag> {
+	/// Find the variant for a tag value.
+	fn find(&self, matches: impl Fn(&TagValue) -> bool) -> Option<Tag> {
+		self.values
+			.iter()
+			.find(|(value, _)| matches(value))
+			.map(|&(_, tag)| tag)
+	}
+
+	/// Resolve a tag value to a known variant, or handle it as unknown tag value.
+	fn resolve<'de, E: Error>(
+		self,
+		known: Option<Tag>,
+		value: &dyn std::fmt::Display,
+		content: impl FnOnce() -> Content<'de>,
+	) -> Result<DeserializedTag<'de, Tag>, E> {
+		if let Some(tag) = known {
+			return Ok(DeserializedTag::Known(tag));
+		}
+		match self.unknown {
+			UnknownTag::Error => Err(E::custom(UnknownTagError {
+				value,
+				expected: self.values,
+			})),
+			UnknownTag::Variant(tag) => Ok(DeserializedTag::Known(tag)),
+			UnknownTag::Capture => Ok(DeserializedTag::Unknown(content())),
+		}
+	}
+}
+
+impl<'de, Tag: Copy> serde::de::DeserializeSeed<'de> for TagSeed<Tag> {
+	type Value = DeserializedTag<'de, Tag>;
+
+	fn deserialize<D: serde::Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+		deserializer.deserialize_any(self)
+	}
+}
+
+impl<'de, Tag: Copy> Visitor<'de> for TagSeed<Tag> {
+	type Value = DeserializedTag<'de, Tag>;
+
+	fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+		formatter.write_str("a tag value")
+	}
+
+	fn visit_bool<E: Error>(self, value: bool) -> Result<Self::Value, E> {
+		let known = self.find(|x| *x == TagValue::Bool(value));
+		self.resolve(known, &value, || Content::Bool(value))
+	}
+
+	fn visit_i64<E: Error>(self, value: i64) -> Result<Self::Value, E> {
+		let known = self.find(|x| *x == TagValue::Int(value.into()));
+		self.resolve(known, &value, || Content::I64(value))
+	}
+
+	fn visit_u64<E: Error>(self, value: u64) -> Result<Self::Value, E> {
+		let known = self.find(|x| *x == TagValue::Int(value.into()));
+		self.resolve(known, &value, || Content::U64(value))
+	}
+
+	fn visit_str<E: Error>(self, value: &str) -> Result<Self::Value, E> {
+		let known = self.find(|x| matches!(x, TagValue::Str(x) if *x == value));
+		self.resolve(known, &value, || Content::String(value.into()))
+	}
+
+	fn visit_borrowed_str<E: Error>(self, value: &'de str) -> Result<Self::Value, E> {
+		let known = self.find(|x| matches!(x, TagValue::Str(x) if *x == value));
+		self.resolve(known, &value, || Content::Str(value))
+	}
+
+	fn visit_string<E: Error>(self, value: String) -> Result<Self::Value, E> {
+		// Avoid copying the string if it is captured.
+		if let UnknownTag::Capture = self.unknown {
+			if self.find(|x| matches!(x, TagValue::Str(x) if *x == value)).is_none() {
+				return Ok(DeserializedTag::Unknown(Content::String(value)));
+			}
+		}
+		self.visit_str(&value)
+	}
+}
+
+/// Parse a tag value from a string.
+///
+/// Tag values that do not match any variant are mapped to `other` if it is given, or produce an error otherwise.
+pub fn parse_tag_value<Tag: Copy>(
+	input: &str,
+	values: &'static [(TagValue, Tag)],
+	other: Option<Tag>,
+) -> Result<Tag, crate::ParseTagError> {
+	let known = values.iter().find(|(value, _)| value.matches_str(input));
+	match (known, other) {
+		(Some(&(_, tag)), _) => Ok(tag),
+		(None, Some(tag)) => Ok(tag),
+		(None, None) => Err(crate::ParseTagError::new(
+			UnknownTagError {
+				value: &input,
+				expected: values,
+			}
+			.to_string(),
+		)),
+	}
+}
+
+/// Error for a tag value that does not match any variant.
+///
+/// Formatted like the `unknown_variant` error of `serde`, but supports non-string tag values.
+struct UnknownTagError<'a, Tag: 'static> {
+	value: &'a dyn std::fmt::Display,
+	expected: &'static [(TagValue, Tag)],
+}
+
+impl<Tag> std::fmt::Display for UnknownTagError<'_, Tag> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "unknown variant `{}`, ", self.value)?;
+		match self.expected {
+			[] => write!(f, "there are no variants"),
+			[(a, _)] => write!(f, "expected `{a}`"),
+			[(a, _), (b, _)] => write!(f, "expected `{a}` or `{b}`"),
+			[(first, _), rest @ ..] => {
+				write!(f, "expected one of `{first}`")?;
+				for (value, _) in rest {
+					write!(f, ", `{value}`")?;
+				}
+				Ok(())
+			},
+		}
+	}
+}
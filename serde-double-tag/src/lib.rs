@@ -47,11 +47,59 @@
 //! Since each variant uses a different field name, they will never conflict.
 //! And since there is still a separate field for the enum tag, you can still known which variant is actually active.
 //!
+//! To keep the data of inactive variants when deserializing and serializing a value again, wrap the enum in [`Preserving`].
+//!
+//! To read or write the content of all variants at once, derive [`AllVariants`] for the enum.
+//! This generates a companion struct (`FriendAllVariants`) with the tag field and an optional field for the content of each variant,
+//! a tag enum (`FriendAllVariantsTag`) and a payload struct for each variant with content (`FriendHumanData`, `FriendDogData`).
+//!
+//! To pass the content of a single struct variant around on its own, derive [`VariantStructs`] for the enum.
+//! This generates a public struct for each struct variant (`FriendHuman`, `FriendDog`) with the same fields and serde attributes as the content of the variant,
+//! with `From` conversions into the enum and `TryFrom` conversions from the enum.
+//!
+//! To get a public enum with the variants of the enum but without content, derive [`TagEnum`] and set the name with `#[serde(tag_enum = "FriendTag")]`.
+//! The tag enum (de)serializes as the tag value, implements `Display` and `FromStr`, and has an `ALL` constant with all variants.
+//! It also adds a `tag()` function to the original enum to get the tag of the active variant.
+//! The `Deserialize` derive then also generates `Friend::deserialize_as(deserializer, FriendTag::Dog)`,
+//! which deserializes the content of a specific variant while ignoring the tag field.
+//!
+//! A double tagged enum can be embedded in a struct with `#[serde(flatten)]`.
+//! The tag field does not need to be the first field, and the fields of the parent struct are not seen by the enum, even with `#[serde(deny_unknown_fields)]`.
+//! The generated JSON schema is merged into the schema of the parent struct.
+//! Note that `schemars` only keeps the variant subschemas (`allOf` or `if`/`then`) of the first flattened field that has any.
+//! With multiple flattened double tagged enums, the schema still requires all tag fields, but only checks the content fields of the first enum.
+//!
+//! Derive [`DoubleTagged`] to expose the tag field name, the tag values and the content field names of the enum.
+//! This can be used to refer to the serialized representation generically, for example to build database indexes.
+//! It can be combined with the other derive macros, but not with a `#[serde(catch_all)]` variant.
+//!
 //! Currently supported `serde` attributes:
 //! * `#[serde(rename = "...")]
 //! * `#[serde(rename_all = "...")]
 //! * `#[serde(rename_all_fields = "...")]
 //! * `#[serde(deny_unknown_fields = "...")]
+//! * `#[serde(other)]` on a unit variant to accept unknown tags.
+//! * `#[serde(catch_all)]` on a `{ tag, content }` or `(tag, content)` variant to capture unknown tags and their content. All tag values must be strings.
+//! * `#[serde(alias = "...")]` on variants, accepted as tag value and as content field name.
+//! * `#[serde(content = "...")]` on variants, to use a content field name different from the tag value.
+//! * `#[serde(content = "...")]` on the enum, as template for the content field names with a `{tag}` placeholder.
+//! * `#[serde(rename_all_content = "...")]` to rename content fields independently of the tag values.
+//! * `#[serde(tag_value = ...)]` on variants, to use a string, integer or boolean tag value. A string tag value is also used as content field name, like a renamed variant. For integer and boolean tag values, the content field name is derived from the variant name.
+//! * `#[serde(discriminant_as_tag)]` on the enum, to use the (integer literal) discriminants of the variants as tag values.
+//! * `#[serde(infer_tag)]` on the enum, to infer the variant from the content field if the tag field is missing.
+//! * `#[serde(validate_inactive)]` on the enum, to validate the content fields of inactive variants instead of ignoring them.
+//! * `#[serde(inactive_content = "allow")]` or `#[serde(inactive_content = "deny")]` on the enum, to accept or reject content fields of inactive variants independently of `deny_unknown_fields`.
+//! * `#[serde(all_variants = "...")]` on the enum, to set the name of the companion struct generated by `#[derive(AllVariants)]`.
+//! * `#[serde(tag_enum = "...")]` on the enum, to set the name of the tag enum generated by `#[derive(TagEnum)]`.
+//! * `#[serde(null_inactive)]` on the enum, to serialize the content fields of inactive variants as `null`, for example to clear them with a JSON merge patch. These `null` values are always accepted when deserializing.
+//! * `#[serde(unit_content = "omit")]`, `#[serde(unit_content = "null")]` or `#[serde(unit_content = "empty")]` on the enum, to require the content field of unit variants to be omitted, `null` or an empty map. Without it, the content field is omitted when serializing, and may be omitted or `null` when deserializing.
+//! * `#[serde(empty_as_unit)]` on the enum, to encode tuple and struct variants without fields like unit variants. Without `#[serde(unit_content)]`, an empty sequence or map is also accepted for these variants when deserializing.
+//! * `#[serde(default)]` or `#[serde(default = "...")]` on fields of tuple and struct variants, to use a default value for missing fields. These fields are not required in the JSON schema.
+//! * `#[serde(default)]` on tuple and struct variants or on the enum, to fill in missing fields from the `Default` implementation of the variant fields. A missing content field gives the default content.
+//! * `#[serde(skip_serializing_if = "...")]` on fields of tuple and struct variants. These fields are not required in the JSON schema.
+//! * `#[serde(with = "...")]`, `#[serde(serialize_with = "...")]` and `#[serde(deserialize_with = "...")]` on fields of tuple and struct variants. With `#[derive(JsonSchema)]`, the schema of these fields can be overridden with `#[schemars(with = "...")]` or `#[schemars(schema_with = "...")]`.
+//! * `#[serde(skip)]`, `#[serde(skip_serializing)]` and `#[serde(skip_deserializing)]` on fields of tuple and struct variants. Fields skipped when deserializing are set to their default value. Skipped fields are left out of the JSON schema.
+//! * `#[serde(derive_json_schema)]` on the enum, to also derive `JsonSchema` for the types generated by `#[derive(AllVariants)]`, `#[derive(VariantStructs)]` and `#[derive(TagEnum)]`. Requires the `schemars` feature.
 
 #![deny(missing_docs)]
 #![deny(missing_debug_implementations)]
@@ -60,6 +108,15 @@
 #[allow(missing_debug_implementations)]
 pub mod internal__;
 
+mod double_tagged;
+pub use double_tagged::{DoubleTagged, VariantInfo};
+
+mod error;
+pub use error::{MissingContent, ParseTagError};
+
+mod preserving;
+pub use preserving::Preserving;
+
 /// Derive [`serde::Serialize`] for an enum using the double-tagged enum representation.
 ///
 /// See the module documentation for details on the enum representation.
@@ -70,9 +127,58 @@ pub use serde_double_tag_derive::Serialize;
 /// See the module documentation for details on the enum representation.
 pub use serde_double_tag_derive::Deserialize;
 
+/// Derive a companion struct that holds the content of all variants of an enum.
+///
+/// See the module documentation for details.
+pub use serde_double_tag_derive::AllVariants;
+
+/// Derive a public tag enum with a unit variant for each variant of an enum.
+///
+/// The name of the tag enum must be set with `#[serde(tag_enum = "...")]`.
+/// See the module documentation for details.
+pub use serde_double_tag_derive::TagEnum;
+
+/// Derive [`DoubleTagged`] for an enum, to expose the tag field name, the tag values and the content field names.
+///
+/// See the module documentation for details.
+pub use serde_double_tag_derive::DoubleTagged;
+
+/// Derive a public struct for each struct variant of an enum, with conversions to and from the enum.
+///
+/// See the module documentation for details.
+pub use serde_double_tag_derive::VariantStructs;
+
 /// Derive [`schemars::JsonSchema`] for an enum using the double-tagged enum representation.
 ///
 /// See the module documentation for details on the enum representation.
 #[cfg(feature = "schemars")]
 #[cfg_attr(feature = "doc-cfg", doc(cfg(feature = "schemars")))]
 pub use serde_double_tag_derive::JsonSchema;
+
+/// A `#[serde(catch_all)]` variant can not be combined with integer tag values:
+///
+/// ```compile_fail
+/// #[derive(serde_double_tag::Deserialize)]
+/// #[serde(tag = "type")]
+/// enum Shape {
+///   #[serde(tag_value = 1)]
+///   Circle(f64),
+///   #[serde(catch_all)]
+///   Unknown { tag: String, content: Option<serde_json::Value> },
+/// }
+/// ```
+///
+/// Or with discriminants as tag values:
+///
+/// ```compile_fail
+/// #[derive(serde_double_tag::Serialize)]
+/// #[serde(tag = "type", discriminant_as_tag)]
+/// #[repr(u8)]
+/// enum Shape {
+///   Circle(f64) = 1,
+///   #[serde(catch_all)]
+///   Unknown { tag: String, content: Option<serde_json::Value> },
+/// }
+/// ```
+#[cfg(doctest)]
+pub struct CatchAllWithIntegerTags;
@@ -0,0 +1,401 @@
+use serde::ser::{Error, Impossible, SerializeMap, SerializeStruct};
+
+use crate::internal__::{Content, ContentFields};
+
+/// Wrapper for a double tagged enum that preserves the content of inactive variants.
+///
+/// Normally, deserializing a double tagged enum discards the content fields of all inactive variants.
+/// When the value is serialized again, that data is lost.
+///
+/// This wrapper remembers the content fields of all variants as they were deserialized,
+/// and writes them back when serializing.
+/// The content of the active variant is always taken from the wrapped value.
+/// If the active variant is changed, the content that was deserialized for the previously active variant
+/// is written back instead, so switching between variants does not lose any data.
+///
+/// Fields that are not the tag or a content field are not preserved.
+///
+/// ```
+/// # use serde_double_tag::Preserving;
+/// #[derive(serde_double_tag::Deserialize, serde_double_tag::Serialize)]
+/// #[serde(tag = "species")]
+/// #[serde(rename_all = "snake_case")]
+/// enum Friend {
+///   Human { name: String },
+///   Dog { name: String },
+/// }
+///
+/// let mut friend: Preserving<Friend> = serde_json::from_str(r#"{"species":"dog","dog":{"name":"Scrappy"}}"#).unwrap();
+/// *friend = Friend::Human { name: "Zohan".into() };
+/// assert_eq!(
+///   serde_json::to_string(&friend).unwrap(),
+///   r#"{"species":"human","human":{"name":"Zohan"},"dog":{"name":"Scrappy"}}"#,
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct Preserving<E> {
+	value: E,
+	preserved: Vec<PreservedField>,
+}
+
+/// A preserved content field.
+#[derive(Debug, Clone)]
+struct PreservedField {
+	name: &'static str,
+	variant_index: usize,
+	content: Content<'static>,
+}
+
+impl<E> Preserving<E> {
+	/// Wrap a value without any preserved content.
+	pub fn new(value: E) -> Self {
+		Self {
+			value,
+			preserved: Vec::new(),
+		}
+	}
+
+	/// Get the wrapped value, discarding the preserved content.
+	pub fn into_inner(self) -> E {
+		self.value
+	}
+
+	/// Discard the preserved content of all variants.
+	pub fn discard_preserved(&mut self) {
+		self.preserved.clear();
+	}
+}
+
+impl<E> From<E> for Preserving<E> {
+	fn from(value: E) -> Self {
+		Self::new(value)
+	}
+}
+
+impl<E> std::ops::Deref for Preserving<E> {
+	type Target = E;
+
+	fn deref(&self) -> &Self::Target {
+		&self.value
+	}
+}
+
+impl<E> std::ops::DerefMut for Preserving<E> {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.value
+	}
+}
+
+impl<'de, E> serde::Deserialize<'de> for Preserving<E>
+where
+	E: serde::Deserialize<'de> + ContentFields,
+{
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		let content = Content::deserialize(deserializer)?;
+		let mut preserved = Vec::new();
+		if let Content::Map(entries) = &content {
+			for (key, value) in entries {
+				let Some(key) = key.as_str() else {
+					continue;
+				};
+				if let Some(&(name, variant_index)) = E::CONTENT_FIELDS.iter().find(|(name, _)| *name == key) {
+					preserved.push(PreservedField {
+						name,
+						variant_index,
+						content: value.clone().into_owned(),
+					});
+				}
+			}
+		}
+		let value = content.deserialize_into()?;
+		Ok(Self { value, preserved })
+	}
+}
+
+impl<E> serde::Serialize for Preserving<E>
+where
+	E: serde::Serialize + ContentFields,
+{
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let active = self.value.variant_index();
+		let extra: Vec<_> = self
+			.preserved
+			.iter()
+			.filter(|field| Some(field.variant_index) != active)
+			.collect();
+
+		// With `#[serde(null_inactive)]`, some extra fields replace a `null` field instead of being added.
+		let null_fields = self.value.null_content_fields();
+		let replaced = extra.iter().filter(|field| null_fields.contains(&field.name)).count();
+		self.value.serialize(ExtraFieldsSerializer {
+			inner: serializer,
+			added: extra.len() - replaced,
+			extra,
+		})
+	}
+}
+
+#[cfg(feature = "schemars")]
+impl<E: schemars::JsonSchema> schemars::JsonSchema for Preserving<E> {
+	fn is_referenceable() -> bool {
+		E::is_referenceable()
+	}
+
+	fn schema_name() -> String {
+		E::schema_name()
+	}
+
+	fn schema_id() -> std::borrow::Cow<'static, str> {
+		E::schema_id()
+	}
+
+	fn json_schema(generator: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+		E::json_schema(generator)
+	}
+}
+
+/// A serializer that adds extra fields to a struct or map.
+///
+/// Anything other than a struct or map is rejected.
+struct ExtraFieldsSerializer<'a, S> {
+	inner: S,
+	extra: Vec<&'a PreservedField>,
+
+	/// The number of extra fields that are not already written by the value itself.
+	added: usize,
+}
+
+/// Serialize the remaining fields of a struct or map, followed by the extra fields.
+///
+/// Fields of the value with the same name as an extra field are replaced by the extra field.
+struct ExtraFields<'a, S> {
+	inner: S,
+	extra: Vec<&'a PreservedField>,
+
+	/// The extra field that replaces the value of the last serialized map key.
+	replacement: Option<&'a PreservedField>,
+}
+
+/// Create the error for a value that is not a struct or map.
+fn not_a_map<E: Error>() -> E {
+	E::custom("a double tagged enum must serialize as a struct or map")
+}
+
+/// Implement serializer functions that reject the value.
+macro_rules! reject {
+	($($name:ident($($arg:ident: $type:ty),*) -> $output:ty;)*) => {
+		$(
+			fn $name(self, $(_: $type),*) -> Result<$output, Self::Error> {
+				Err(not_a_map())
+			}
+		)*
+	};
+}
+
+impl<'a, S: serde::Serializer> serde::Serializer for ExtraFieldsSerializer<'a, S> {
+	type Error = S::Error;
+	type Ok = S::Ok;
+	type SerializeMap = ExtraFields<'a, S::SerializeMap>;
+	type SerializeSeq = Impossible<S::Ok, S::Error>;
+	type SerializeStruct = ExtraFields<'a, S::SerializeStruct>;
+	type SerializeStructVariant = Impossible<S::Ok, S::Error>;
+	type SerializeTuple = Impossible<S::Ok, S::Error>;
+	type SerializeTupleStruct = Impossible<S::Ok, S::Error>;
+	type SerializeTupleVariant = Impossible<S::Ok, S::Error>;
+
+	reject! {
+		serialize_bool(value: bool) -> S::Ok;
+		serialize_i8(value: i8) -> S::Ok;
+		serialize_i16(value: i16) -> S::Ok;
+		serialize_i32(value: i32) -> S::Ok;
+		serialize_i64(value: i64) -> S::Ok;
+		serialize_i128(value: i128) -> S::Ok;
+		serialize_u8(value: u8) -> S::Ok;
+		serialize_u16(value: u16) -> S::Ok;
+		serialize_u32(value: u32) -> S::Ok;
+		serialize_u64(value: u64) -> S::Ok;
+		serialize_u128(value: u128) -> S::Ok;
+		serialize_f32(value: f32) -> S::Ok;
+		serialize_f64(value: f64) -> S::Ok;
+		serialize_char(value: char) -> S::Ok;
+		serialize_str(value: &str) -> S::Ok;
+		serialize_bytes(value: &[u8]) -> S::Ok;
+		serialize_none() -> S::Ok;
+		serialize_unit() -> S::Ok;
+		serialize_unit_struct(name: &'static str) -> S::Ok;
+		serialize_unit_variant(name: &'static str, index: u32, variant: &'static str) -> S::Ok;
+		serialize_seq(len: Option<usize>) -> Self::SerializeSeq;
+		serialize_tuple(len: usize) -> Self::SerializeTuple;
+		serialize_tuple_struct(name: &'static str, len: usize) -> Self::SerializeTupleStruct;
+		serialize_tuple_variant(name: &'static str, index: u32, variant: &'static str, len: usize) -> Self::SerializeTupleVariant;
+		serialize_struct_variant(name: &'static str, index: u32, variant: &'static str, len: usize) -> Self::SerializeStructVariant;
+	}
+
+	fn serialize_some<T: serde::Serialize + ?Sized>(self, _value: &T) -> Result<S::Ok, S::Error> {
+		Err(not_a_map())
+	}
+
+	fn serialize_newtype_struct<T: serde::Serialize + ?Sized>(
+		self,
+		_name: &'static str,
+		_value: &T,
+	) -> Result<S::Ok, S::Error> {
+		Err(not_a_map())
+	}
+
+	fn serialize_newtype_variant<T: serde::Serialize + ?Sized>(
+		self,
+		_name: &'static str,
+		_variant_index: u32,
+		_variant: &'static str,
+		_value: &T,
+	) -> Result<S::Ok, S::Error> {
+		Err(not_a_map())
+	}
+
+	fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, S::Error> {
+		let len = len.map(|len| len + self.added);
+		Ok(ExtraFields {
+			inner: self.inner.serialize_map(len)?,
+			extra: self.extra,
+			replacement: None,
+		})
+	}
+
+	fn serialize_struct(self, name: &'static str, len: usize) -> Result<Self::SerializeStruct, S::Error> {
+		let len = len + self.added;
+		Ok(ExtraFields {
+			inner: self.inner.serialize_struct(name, len)?,
+			extra: self.extra,
+			replacement: None,
+		})
+	}
+}
+
+impl<M: SerializeMap> SerializeMap for ExtraFields<'_, M> {
+	type Error = M::Error;
+	type Ok = M::Ok;
+
+	fn serialize_key<T: serde::Serialize + ?Sized>(&mut self, key: &T) -> Result<(), M::Error> {
+		// Fields written as `null` for inactive variants are replaced by the preserved content.
+		if let Ok(name) = key.serialize(KeyName) {
+			if let Some(index) = self.extra.iter().position(|field| field.name == name) {
+				self.replacement = Some(self.extra.remove(index));
+			}
+		}
+		self.inner.serialize_key(key)
+	}
+
+	fn serialize_value<T: serde::Serialize + ?Sized>(&mut self, value: &T) -> Result<(), M::Error> {
+		match self.replacement.take() {
+			Some(field) => self.inner.serialize_value(&field.content),
+			None => self.inner.serialize_value(value),
+		}
+	}
+
+	fn end(mut self) -> Result<M::Ok, M::Error> {
+		for field in self.extra {
+			self.inner.serialize_entry(field.name, &field.content)?;
+		}
+		self.inner.end()
+	}
+}
+
+impl<M: SerializeStruct> SerializeStruct for ExtraFields<'_, M> {
+	type Error = M::Error;
+	type Ok = M::Ok;
+
+	fn serialize_field<T: serde::Serialize + ?Sized>(&mut self, key: &'static str, value: &T) -> Result<(), M::Error> {
+		// Fields written as `null` for inactive variants are replaced by the preserved content.
+		match self.extra.iter().position(|field| field.name == key) {
+			Some(index) => {
+				let field = self.extra.remove(index);
+				self.inner.serialize_field(key, &field.content)
+			},
+			None => self.inner.serialize_field(key, value),
+		}
+	}
+
+	fn skip_field(&mut self, key: &'static str) -> Result<(), M::Error> {
+		self.inner.skip_field(key)
+	}
+
+	fn end(mut self) -> Result<M::Ok, M::Error> {
+		for field in self.extra {
+			self.inner.serialize_field(field.name, &field.content)?;
+		}
+		self.inner.end()
+	}
+}
+
+/// A serializer that gets the name of a map key.
+///
+/// Anything other than a string is rejected.
+struct KeyName;
+
+impl serde::Serializer for KeyName {
+	type Error = serde::de::value::Error;
+	type Ok = String;
+	type SerializeMap = Impossible<String, Self::Error>;
+	type SerializeSeq = Impossible<String, Self::Error>;
+	type SerializeStruct = Impossible<String, Self::Error>;
+	type SerializeStructVariant = Impossible<String, Self::Error>;
+	type SerializeTuple = Impossible<String, Self::Error>;
+	type SerializeTupleStruct = Impossible<String, Self::Error>;
+	type SerializeTupleVariant = Impossible<String, Self::Error>;
+
+	reject! {
+		serialize_bool(value: bool) -> String;
+		serialize_i8(value: i8) -> String;
+		serialize_i16(value: i16) -> String;
+		serialize_i32(value: i32) -> String;
+		serialize_i64(value: i64) -> String;
+		serialize_i128(value: i128) -> String;
+		serialize_u8(value: u8) -> String;
+		serialize_u16(value: u16) -> String;
+		serialize_u32(value: u32) -> String;
+		serialize_u64(value: u64) -> String;
+		serialize_u128(value: u128) -> String;
+		serialize_f32(value: f32) -> String;
+		serialize_f64(value: f64) -> String;
+		serialize_char(value: char) -> String;
+		serialize_bytes(value: &[u8]) -> String;
+		serialize_none() -> String;
+		serialize_unit() -> String;
+		serialize_unit_struct(name: &'static str) -> String;
+		serialize_unit_variant(name: &'static str, index: u32, variant: &'static str) -> String;
+		serialize_seq(len: Option<usize>) -> Self::SerializeSeq;
+		serialize_tuple(len: usize) -> Self::SerializeTuple;
+		serialize_tuple_struct(name: &'static str, len: usize) -> Self::SerializeTupleStruct;
+		serialize_tuple_variant(name: &'static str, index: u32, variant: &'static str, len: usize) -> Self::SerializeTupleVariant;
+		serialize_map(len: Option<usize>) -> Self::SerializeMap;
+		serialize_struct(name: &'static str, len: usize) -> Self::SerializeStruct;
+		serialize_struct_variant(name: &'static str, index: u32, variant: &'static str, len: usize) -> Self::SerializeStructVariant;
+	}
+
+	fn serialize_str(self, value: &str) -> Result<String, Self::Error> {
+		Ok(value.into())
+	}
+
+	fn serialize_some<T: serde::Serialize + ?Sized>(self, _value: &T) -> Result<String, Self::Error> {
+		Err(Error::custom("map key is not a string"))
+	}
+
+	fn serialize_newtype_struct<T: serde::Serialize + ?Sized>(
+		self,
+		_name: &'static str,
+		_value: &T,
+	) -> Result<String, Self::Error> {
+		Err(Error::custom("map key is not a string"))
+	}
+
+	fn serialize_newtype_variant<T: serde::Serialize + ?Sized>(
+		self,
+		_name: &'static str,
+		_variant_index: u32,
+		_variant: &'static str,
+		_value: &T,
+	) -> Result<String, Self::Error> {
+		Err(Error::custom("map key is not a string"))
+	}
+}
@@ -0,0 +1,124 @@
+use assert2::{assert, let_assert};
+
+#[track_caller]
+fn parse<'a, T: serde::Deserialize<'a>>(data: &'a str) -> T {
+	match serde_json::from_str(data) {
+		Ok(value) => value,
+		Err(e) => panic!("failed to parse {data}: {e}"),
+	}
+}
+
+#[track_caller]
+fn json<T: serde::Serialize>(value: T) -> String {
+	let_assert!(Ok(json) = serde_json::to_string(&value));
+	json
+}
+
+#[derive(Debug, PartialEq, serde_double_tag::AllVariants)]
+#[serde(rename_all = "snake_case")]
+#[serde(tag = "species")]
+enum Friend {
+	Human {
+		name: String,
+		hobbies: Vec<String>,
+	},
+	#[serde(alias = "doggo")]
+	Dog {
+		name: String,
+		color: String,
+	},
+	Cat(String),
+	Nobody,
+}
+
+#[test]
+fn all_variants_deserialize() {
+	let all: FriendAllVariants = parse(
+		r#"{"species":"doggo","human":{"name":"Zohan","hobbies":[]},"doggo":{"name":"Scrappy","color":"white"}}"#,
+	);
+	assert!(all.species == FriendAllVariantsTag::Dog);
+	let_assert!(Some(human) = &all.human);
+	assert!(human.name == "Zohan");
+	let_assert!(Some(dog) = &all.dog);
+	assert!(dog.name == "Scrappy");
+	assert!(dog.color == "white");
+	assert!(all.cat.is_none());
+
+	let_assert!(Ok(friend) = Friend::try_from(all));
+	assert!(
+		friend
+			== Friend::Dog {
+				name: "Scrappy".into(),
+				color: "white".into()
+			}
+	);
+}
+
+#[test]
+fn all_variants_serialize() {
+	let all = FriendAllVariants {
+		species: FriendAllVariantsTag::Cat,
+		human: None,
+		dog: Some(FriendDogData {
+			name: "Scrappy".into(),
+			color: "white".into(),
+		}),
+		cat: Some(FriendCatData("Tom".into())),
+	};
+	assert!(json(&all) == r#"{"species":"cat","dog":{"name":"Scrappy","color":"white"},"cat":"Tom"}"#);
+}
+
+#[test]
+fn all_variants_conversions() {
+	let all = FriendAllVariants::from(Friend::Cat("Tom".into()));
+	assert!(json(&all) == r#"{"species":"cat","cat":"Tom"}"#);
+	assert!(Friend::try_from(all) == Ok(Friend::Cat("Tom".into())));
+
+	let all = FriendAllVariants::from(Friend::Nobody);
+	assert!(json(&all) == r#"{"species":"nobody"}"#);
+	assert!(Friend::try_from(all) == Ok(Friend::Nobody));
+
+	let all: FriendAllVariants = parse(r#"{"species":"human","cat":"Tom"}"#);
+	let_assert!(Err(e) = Friend::try_from(all));
+	assert!(e.content_field_name() == "human");
+}
+
+#[test]
+fn all_variants_tag_values() {
+	#[derive(serde_double_tag::AllVariants)]
+	#[serde(tag = "type", all_variants = "Shapes")]
+	#[allow(unused)]
+	enum Shape {
+		#[serde(tag_value = 1)]
+		Circle(f64),
+		#[serde(tag_value = 2)]
+		Square(f64),
+		#[serde(other)]
+		Unknown,
+	}
+
+	let shapes: Shapes = parse(r#"{"type":2,"Circle":1.0,"Square":2.0}"#);
+	assert!(shapes.r#type == ShapesTag::Square);
+	assert!(shapes.Circle.as_ref().map(|x| x.0) == Some(1.0));
+	assert!(json(&shapes) == r#"{"type":2,"Circle":1.0,"Square":2.0}"#);
+
+	let shapes: Shapes = parse(r#"{"type":3}"#);
+	assert!(shapes.r#type == ShapesTag::Unknown);
+}
+
+#[test]
+fn all_variants_without_json_schema() {
+	// Not a `JsonSchema` type: the generated types only derive `JsonSchema` with `#[serde(derive_json_schema)]`.
+	#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+	struct Name(String);
+
+	#[derive(serde_double_tag::AllVariants)]
+	#[serde(tag = "type")]
+	#[allow(unused)]
+	enum Pet {
+		Dog(Name),
+	}
+
+	let pets: PetAllVariants = parse(r#"{"type":"Dog","Dog":"Scrappy"}"#);
+	assert!(pets.Dog.map(|x| x.0) == Some(Name("Scrappy".into())));
+}
@@ -0,0 +1,783 @@
+use assert2::{assert, let_assert};
+
+#[track_caller]
+fn parse<'a, T: serde::Deserialize<'a> + std::fmt::Debug>(data: &'a str) -> T {
+	let_assert!(Ok(value) = serde_json::from_str(data));
+	value
+}
+
+#[track_caller]
+fn parse_err<'a, T: serde::Deserialize<'a> + std::fmt::Debug>(data: &'a str) -> String {
+	let_assert!(Err(e) = serde_json::from_str::<T>(data));
+	e.to_string()
+}
+
+#[derive(Debug, PartialEq, serde_double_tag::Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[serde(tag = "type")]
+enum MyEnum {
+	Unit,
+	EmptyTuple(),
+	NewType(String),
+	Tuple(u8, String),
+	EmptyStruct {},
+	Struct { field_a: String, field_b: u8 },
+}
+
+#[test]
+fn deserialize_enum() {
+	assert!(parse::<MyEnum>(r#"{"type":"unit"}"#) == MyEnum::Unit);
+	assert!(parse::<MyEnum>(r#"{"type":"empty_tuple","empty_tuple":[]}"#) == MyEnum::EmptyTuple());
+	assert!(parse::<MyEnum>(r#"{"type":"new_type","new_type":"hello"}"#) == MyEnum::NewType("hello".into()));
+	assert!(parse::<MyEnum>(r#"{"type":"tuple","tuple":[3,"world"]}"#) == MyEnum::Tuple(3, "world".into()));
+	assert!(parse::<MyEnum>(r#"{"type":"empty_struct","empty_struct":{}}"#) == MyEnum::EmptyStruct {});
+	assert!(
+		parse::<MyEnum>(r#"{"type":"struct","struct":{"field_a":"bye","field_b":7}}"#)
+			== MyEnum::Struct {
+				field_a: "bye".into(),
+				field_b: 7
+			}
+	);
+
+	let error = parse_err::<MyEnum>(r#"{"type":"cat"}"#);
+	assert!(error.contains("unknown variant `cat`"));
+}
+
+#[test]
+fn deserialize_enum_tag_not_first() {
+	assert!(parse::<MyEnum>(r#"{"new_type":"hello","type":"new_type"}"#) == MyEnum::NewType("hello".into()));
+	assert!(parse::<MyEnum>(r#"{"tuple":[3,"world"],"type":"tuple"}"#) == MyEnum::Tuple(3, "world".into()));
+	assert!(
+		parse::<MyEnum>(r#"{"struct":{"field_a":"bye","field_b":7},"type":"struct"}"#)
+			== MyEnum::Struct {
+				field_a: "bye".into(),
+				field_b: 7
+			}
+	);
+
+	// Content of other variants and unknown fields before the tag are ignored.
+	assert!(
+		parse::<MyEnum>(r#"{"extra":1,"new_type":"hello","tuple":[3,"world"],"type":"tuple"}"#)
+			== MyEnum::Tuple(3, "world".into())
+	);
+
+	// Content for the active variant may also follow the tag.
+	assert!(
+		parse::<MyEnum>(r#"{"new_type":"hello","type":"tuple","tuple":[3,"world"]}"#)
+			== MyEnum::Tuple(3, "world".into())
+	);
+
+	let error = parse_err::<MyEnum>(r#"{"new_type":"hello","new_type":"world","type":"new_type"}"#);
+	assert!(error.contains("duplicate field `new_type`"));
+
+	let error = parse_err::<MyEnum>(r#"{"new_type":"hello"}"#);
+	assert!(error.contains("missing field `type`"));
+}
+
+#[test]
+fn deserialize_enum_tag_not_first_deny_unknown_fields() {
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "species")]
+	#[serde(deny_unknown_fields)]
+	enum Friend {
+		Human { name: String },
+		Dog { name: String },
+	}
+	assert!(parse::<Friend>(r#"{"dog":{"name":"Scrappy"},"species":"dog"}"#) == Friend::Dog { name: "Scrappy".into() });
+
+	// Unknown fields and the content of other variants are rejected, also when they appear before the tag.
+	let error = parse_err::<Friend>(r#"{"foo":1,"species":"dog","dog":{"name":"Scrappy"}}"#);
+	assert!(error.contains("unknown field `foo`"));
+	let error = parse_err::<Friend>(r#"{"human":{"name":"Zohan"},"species":"dog","dog":{"name":"Scrappy"}}"#);
+	assert!(error.contains("unexpected field `human`: content of an inactive variant"));
+}
+
+#[test]
+fn deserialize_enum_other() {
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(deny_unknown_fields)]
+	#[serde(tag = "type")]
+	enum MyEnum {
+		NewType(String),
+		#[serde(other)]
+		Unknown,
+	}
+	assert!(parse::<MyEnum>(r#"{"type":"new_type","new_type":"hello"}"#) == MyEnum::NewType("hello".into()));
+	assert!(parse::<MyEnum>(r#"{"type":"unknown"}"#) == MyEnum::Unknown);
+	assert!(parse::<MyEnum>(r#"{"type":"cat","cat":{"name":"Garfield"}}"#) == MyEnum::Unknown);
+	assert!(parse::<MyEnum>(r#"{"cat":{"name":"Garfield"},"type":"cat"}"#) == MyEnum::Unknown);
+}
+
+#[test]
+fn deserialize_enum_catch_all() {
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	enum MyEnum {
+		NewType(String),
+		#[serde(catch_all)]
+		Unknown {
+			tag: String,
+			content: Option<serde_json::Value>,
+		},
+	}
+	assert!(parse::<MyEnum>(r#"{"type":"new_type","new_type":"hello"}"#) == MyEnum::NewType("hello".into()));
+	assert!(
+		parse::<MyEnum>(r#"{"type":"cat","cat":{"name":"Garfield"}}"#)
+			== MyEnum::Unknown {
+				tag: "cat".into(),
+				content: Some(serde_json::json!({"name": "Garfield"})),
+			}
+	);
+	assert!(
+		parse::<MyEnum>(r#"{"cat":{"name":"Garfield"},"other":1,"type":"cat"}"#)
+			== MyEnum::Unknown {
+				tag: "cat".into(),
+				content: Some(serde_json::json!({"name": "Garfield"})),
+			}
+	);
+	assert!(
+		parse::<MyEnum>(r#"{"type":"cat"}"#)
+			== MyEnum::Unknown {
+				tag: "cat".into(),
+				content: None,
+			}
+	);
+}
+
+#[test]
+fn deserialize_enum_catch_all_tuple() {
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	enum MyEnum<T> {
+		NewType(String),
+		#[serde(catch_all)]
+		Unknown(Box<str>, T),
+	}
+	assert!(parse::<MyEnum<u32>>(r#"{"type":"cat","cat":9}"#) == MyEnum::Unknown("cat".into(), 9));
+
+	let error = parse_err::<MyEnum<u32>>(r#"{"type":"cat"}"#);
+	assert!(error.contains("missing field `cat`"));
+}
+
+#[test]
+fn deserialize_enum_rename() {
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize)]
+	#[serde(tag = "type")]
+	enum MyEnum {
+		#[serde(rename = "renamed")]
+		NewType(String),
+	}
+	assert!(parse::<MyEnum>(r#"{"type":"renamed","renamed":"hello"}"#) == MyEnum::NewType("hello".into()));
+	assert!(parse::<MyEnum>(r#"{"renamed":"hello","type":"renamed"}"#) == MyEnum::NewType("hello".into()));
+	let error = parse_err::<MyEnum>(r#"{"type":"NewType","NewType":"hello"}"#);
+	assert!(error.contains("unknown variant `NewType`"));
+}
+
+#[test]
+fn deserialize_enum_alias() {
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	enum MyEnum {
+		#[serde(alias = "old_name", alias = "older_name")]
+		NewType(String),
+		#[serde(alias = "nothing")]
+		Unit,
+	}
+	assert!(parse::<MyEnum>(r#"{"type":"new_type","new_type":"hello"}"#) == MyEnum::NewType("hello".into()));
+	assert!(parse::<MyEnum>(r#"{"type":"old_name","old_name":"hello"}"#) == MyEnum::NewType("hello".into()));
+	assert!(parse::<MyEnum>(r#"{"type":"new_type","older_name":"hello"}"#) == MyEnum::NewType("hello".into()));
+	assert!(parse::<MyEnum>(r#"{"old_name":"hello","type":"new_type"}"#) == MyEnum::NewType("hello".into()));
+	assert!(parse::<MyEnum>(r#"{"type":"nothing"}"#) == MyEnum::Unit);
+
+	// The content field may only appear once, under any of its names.
+	let error = parse_err::<MyEnum>(r#"{"old_name":"hello","new_type":"world","type":"new_type"}"#);
+	assert!(error.contains("duplicate field `new_type`"));
+	let error = parse_err::<MyEnum>(r#"{"new_type":"hello","older_name":"world","type":"new_type"}"#);
+	assert!(error.contains("duplicate field `new_type`"));
+}
+
+#[test]
+fn deserialize_enum_content_name() {
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	enum MyEnum {
+		#[serde(content = "dog_data", alias = "canine")]
+		Dog { name: String },
+		#[serde(content = "nothing")]
+		Unit,
+	}
+	assert!(parse::<MyEnum>(r#"{"type":"dog","dog_data":{"name":"Max"}}"#) == MyEnum::Dog { name: "Max".into() });
+	assert!(parse::<MyEnum>(r#"{"type":"dog","canine":{"name":"Max"}}"#) == MyEnum::Dog { name: "Max".into() });
+	assert!(parse::<MyEnum>(r#"{"dog_data":{"name":"Max"},"type":"dog"}"#) == MyEnum::Dog { name: "Max".into() });
+	assert!(parse::<MyEnum>(r#"{"type":"unit","nothing":null}"#) == MyEnum::Unit);
+
+	let error = parse_err::<MyEnum>(r#"{"type":"dog","dog":{"name":"Max"}}"#);
+	assert!(error.contains("missing field `dog_data`"));
+}
+
+#[test]
+fn deserialize_enum_content_template() {
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	#[serde(content = "{tag}_payload")]
+	enum MyEnum {
+		#[serde(alias = "old")]
+		NewType(String),
+		#[serde(catch_all)]
+		Unknown(String, Option<u8>),
+	}
+	assert!(parse::<MyEnum>(r#"{"type":"new_type","new_type_payload":"hello"}"#) == MyEnum::NewType("hello".into()));
+	assert!(parse::<MyEnum>(r#"{"type":"old","old_payload":"hello"}"#) == MyEnum::NewType("hello".into()));
+	assert!(parse::<MyEnum>(r#"{"cat_payload":9,"type":"cat"}"#) == MyEnum::Unknown("cat".into(), Some(9)));
+}
+
+#[test]
+fn deserialize_enum_rename_all_content() {
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize)]
+	#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+	#[serde(rename_all_content = "camelCase")]
+	#[serde(tag = "type")]
+	enum MyEnum {
+		NewType(String),
+	}
+	assert!(parse::<MyEnum>(r#"{"type":"NEW_TYPE","newType":"hello"}"#) == MyEnum::NewType("hello".into()));
+}
+
+#[test]
+fn deserialize_enum_tag_value() {
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "kind")]
+	#[serde(discriminant_as_tag)]
+	#[repr(u8)]
+	enum MyEnum {
+		Dog {
+			name: String,
+		} = 1,
+		Cat(String),
+		#[serde(tag_value = -7)]
+		Fish = 10,
+		#[serde(tag_value = "bird", alias = "birb")]
+		Bird(u8),
+	}
+	assert!(parse::<MyEnum>(r#"{"kind":1,"dog":{"name":"Max"}}"#) == MyEnum::Dog { name: "Max".into() });
+	assert!(parse::<MyEnum>(r#"{"cat":"Tom","kind":2}"#) == MyEnum::Cat("Tom".into()));
+	assert!(parse::<MyEnum>(r#"{"kind":-7}"#) == MyEnum::Fish);
+	assert!(parse::<MyEnum>(r#"{"kind":"birb","birb":3}"#) == MyEnum::Bird(3));
+
+	let error = parse_err::<MyEnum>(r#"{"kind":3}"#);
+	assert!(error.contains("unknown variant `3`, expected one of `1`, `2`, `-7`, `bird`, `birb`"));
+	let error = parse_err::<MyEnum>(r#"{"kind":"1"}"#);
+	assert!(error.contains("unknown variant `1`"));
+}
+
+#[test]
+fn deserialize_enum_string_tag_value_names_content() {
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize)]
+	#[serde(tag = "type")]
+	enum MyEnum {
+		#[serde(tag_value = "foo")]
+		Struct { a: u8 },
+		#[serde(tag_value = 5)]
+		Int(u8),
+	}
+	assert!(parse::<MyEnum>(r#"{"type":"foo","foo":{"a":1}}"#) == MyEnum::Struct { a: 1 });
+	assert!(parse::<MyEnum>(r#"{"type":5,"Int":2}"#) == MyEnum::Int(2));
+
+	let error = parse_err::<MyEnum>(r#"{"type":"foo","Struct":{"a":1}}"#);
+	assert!(error.contains("missing field `foo`"));
+}
+
+#[test]
+fn deserialize_enum_tag_value_other() {
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize)]
+	#[serde(tag = "kind")]
+	enum MyEnum {
+		#[serde(tag_value = 1)]
+		Dog(String),
+		#[serde(other)]
+		Unknown,
+	}
+	assert!(parse::<MyEnum>(r#"{"kind":1,"Dog":"Max"}"#) == MyEnum::Dog("Max".into()));
+	assert!(parse::<MyEnum>(r#"{"kind":2,"Cat":"Tom"}"#) == MyEnum::Unknown);
+}
+
+#[test]
+fn deserialize_enum_infer_tag() {
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	#[serde(infer_tag)]
+	enum MyEnum {
+		#[serde(alias = "old_name")]
+		NewType(String),
+		Tuple(u8, String),
+		Unit,
+	}
+	assert!(parse::<MyEnum>(r#"{"new_type":"hello"}"#) == MyEnum::NewType("hello".into()));
+	assert!(parse::<MyEnum>(r#"{"old_name":"hello","extra":1}"#) == MyEnum::NewType("hello".into()));
+	assert!(parse::<MyEnum>(r#"{"tuple":[3,"world"]}"#) == MyEnum::Tuple(3, "world".into()));
+	assert!(parse::<MyEnum>(r#"{"unit":null}"#) == MyEnum::Unit);
+
+	// An explicit tag still wins.
+	assert!(parse::<MyEnum>(r#"{"new_type":"hello","type":"unit"}"#) == MyEnum::Unit);
+
+	let error = parse_err::<MyEnum>(r#"{"new_type":"hello","tuple":[3,"world"]}"#);
+	assert!(
+		error.contains("can not infer the variant: found content fields for multiple variants: `new_type` and `tuple`")
+	);
+	let error = parse_err::<MyEnum>(r#"{"extra":1}"#);
+	assert!(error.contains("missing field `type`"));
+}
+
+#[test]
+fn deserialize_enum_validate_inactive() {
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	#[serde(validate_inactive)]
+	enum MyEnum<T> {
+		NewType(String),
+		Struct {
+			value: T,
+		},
+		#[serde(other)]
+		Unknown,
+	}
+	assert!(
+		parse::<MyEnum<u8>>(r#"{"type":"new_type","new_type":"hello","struct":{"value":3}}"#)
+			== MyEnum::NewType("hello".into())
+	);
+	assert!(
+		parse::<MyEnum<u8>>(r#"{"struct":{"value":3},"type":"new_type","new_type":"hello"}"#)
+			== MyEnum::NewType("hello".into())
+	);
+	assert!(
+		parse::<MyEnum<u8>>(r#"{"type":"new_type","new_type":"hello","extra":[1]}"#) == MyEnum::NewType("hello".into())
+	);
+
+	let error = parse_err::<MyEnum<u8>>(r#"{"type":"new_type","new_type":"hello","struct":{"value":"x"}}"#);
+	assert!(error.contains("invalid type: string \"x\", expected u8"));
+	let error = parse_err::<MyEnum<u8>>(r#"{"struct":{},"type":"new_type","new_type":"hello"}"#);
+	assert!(error.contains("missing field `value`"));
+	let error = parse_err::<MyEnum<u8>>(r#"{"type":"new_type","struct":{"value":-1},"new_type":"hello"}"#);
+	assert!(error.contains("expected u8"));
+	let error = parse_err::<MyEnum<u8>>(r#"{"type":"cat","new_type":5}"#);
+	assert!(error.contains("expected a string"));
+}
+
+#[test]
+fn deserialize_enum_deny_unknown_fields() {
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	#[serde(deny_unknown_fields)]
+	enum MyEnum {
+		NewType(String),
+		Tuple(u8, String),
+	}
+	assert!(parse::<MyEnum>(r#"{"type":"new_type","new_type":"hello"}"#) == MyEnum::NewType("hello".into()));
+
+	let error = parse_err::<MyEnum>(r#"{"extra":1,"type":"new_type","new_type":"hello"}"#);
+	assert!(error.contains("unknown field `extra`, expected `type` or `new_type`"));
+	let error = parse_err::<MyEnum>(r#"{"type":"new_type","new_type":"hello","extra":1}"#);
+	assert!(error.contains("unknown field `extra`, expected `type` or `new_type`"));
+	let error = parse_err::<MyEnum>(r#"{"type":"new_type","tuple":[3,"world"],"new_type":"hello"}"#);
+	assert!(error.contains("unexpected field `tuple`: content of an inactive variant"));
+	let error = parse_err::<MyEnum>(r#"{"type":"new_type","new_type":"hello","type":"tuple"}"#);
+	assert!(error.contains("duplicate field `type`"));
+}
+
+#[test]
+fn deserialize_enum_inactive_content() {
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	#[serde(deny_unknown_fields, inactive_content = "allow")]
+	enum AllowInactive {
+		NewType(String),
+		Tuple(u8, String),
+	}
+	assert!(
+		parse::<AllowInactive>(r#"{"tuple":[3,"world"],"type":"new_type","new_type":"hello"}"#)
+			== AllowInactive::NewType("hello".into())
+	);
+	let error = parse_err::<AllowInactive>(r#"{"type":"new_type","new_type":"hello","extra":1}"#);
+	assert!(error.contains("unknown field `extra`"));
+
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	#[serde(inactive_content = "deny")]
+	enum DenyInactive {
+		NewType(String),
+		Tuple(u8, String),
+	}
+	assert!(
+		parse::<DenyInactive>(r#"{"extra":1,"type":"new_type","new_type":"hello"}"#)
+			== DenyInactive::NewType("hello".into())
+	);
+	let error = parse_err::<DenyInactive>(r#"{"tuple":[3,"world"],"type":"new_type","new_type":"hello"}"#);
+	assert!(error.contains("unexpected field `tuple`: content of an inactive variant"));
+}
+
+#[test]
+fn deserialize_enum_as_variant() {
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize, serde_double_tag::TagEnum)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "species", tag_enum = "FriendTag")]
+	#[serde(deny_unknown_fields)]
+	enum Friend {
+		Human { name: String },
+		Dog { name: String },
+		Nobody,
+	}
+
+	let data = r#"{"species":"human","human":{"name":"Zohan"},"dog":{"name":"Scrappy"},"extra":1}"#;
+	let mut deserializer = serde_json::Deserializer::from_str(data);
+	let_assert!(Ok(dog) = Friend::deserialize_as(&mut deserializer, FriendTag::Dog));
+	assert!(dog == Friend::Dog { name: "Scrappy".into() });
+
+	let mut deserializer = serde_json::Deserializer::from_str(data);
+	let_assert!(Ok(human) = Friend::deserialize_as(&mut deserializer, FriendTag::Human));
+	assert!(human == Friend::Human { name: "Zohan".into() });
+
+	let mut deserializer = serde_json::Deserializer::from_str(data);
+	let_assert!(Ok(nobody) = Friend::deserialize_as(&mut deserializer, FriendTag::Nobody));
+	assert!(nobody == Friend::Nobody);
+
+	let mut deserializer = serde_json::Deserializer::from_str(r#"{"species":"dog","human":{"name":"Zohan"}}"#);
+	let_assert!(Err(e) = Friend::deserialize_as(&mut deserializer, FriendTag::Dog));
+	assert!(e.to_string().contains("missing field `dog`"));
+}
+
+#[test]
+fn deserialize_enum_null_inactive() {
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	#[serde(deny_unknown_fields, null_inactive)]
+	enum MyEnum {
+		NewType(String),
+		Tuple(u8, String),
+	}
+	assert!(
+		parse::<MyEnum>(r#"{"tuple":null,"type":"new_type","new_type":"hello"}"#) == MyEnum::NewType("hello".into())
+	);
+	assert!(
+		parse::<MyEnum>(r#"{"type":"new_type","new_type":"hello","tuple":null}"#) == MyEnum::NewType("hello".into())
+	);
+	let error = parse_err::<MyEnum>(r#"{"type":"new_type","new_type":"hello","tuple":[3,"world"]}"#);
+	assert!(error.contains("unexpected field `tuple`: content of an inactive variant"));
+
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	#[serde(validate_inactive, infer_tag, null_inactive)]
+	enum Validated {
+		NewType(String),
+		Tuple(u8, String),
+	}
+	assert!(
+		parse::<Validated>(r#"{"type":"new_type","new_type":"hello","tuple":null}"#)
+			== Validated::NewType("hello".into())
+	);
+	assert!(parse::<Validated>(r#"{"tuple":null,"new_type":"hello"}"#) == Validated::NewType("hello".into()));
+}
+
+#[test]
+fn deserialize_enum_unit_content() {
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type", unit_content = "null")]
+	enum NullUnit {
+		Unit,
+	}
+	assert!(parse::<NullUnit>(r#"{"type":"unit","unit":null}"#) == NullUnit::Unit);
+	assert!(parse_err::<NullUnit>(r#"{"type":"unit"}"#).contains("missing field `unit`"));
+	assert!(parse_err::<NullUnit>(r#"{"type":"unit","unit":{}}"#).contains("invalid type: map, expected unit"));
+
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type", unit_content = "empty")]
+	enum EmptyUnit {
+		Unit,
+	}
+	assert!(parse::<EmptyUnit>(r#"{"type":"unit","unit":{}}"#) == EmptyUnit::Unit);
+	assert!(parse_err::<EmptyUnit>(r#"{"type":"unit"}"#).contains("missing field `unit`"));
+	assert!(parse_err::<EmptyUnit>(r#"{"type":"unit","unit":null}"#).contains("expected an empty map"));
+	assert!(parse_err::<EmptyUnit>(r#"{"type":"unit","unit":{"a":1}}"#).contains("expected an empty map"));
+
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type", unit_content = "omit")]
+	enum OmitUnit {
+		Unit,
+	}
+	assert!(parse::<OmitUnit>(r#"{"type":"unit"}"#) == OmitUnit::Unit);
+	let error = parse_err::<OmitUnit>(r#"{"type":"unit","unit":null}"#);
+	assert!(error.contains("unexpected field `unit`: unit variants have no content"));
+}
+
+#[test]
+fn deserialize_enum_empty_as_unit() {
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type", empty_as_unit)]
+	enum MyEnum {
+		EmptyTuple(),
+		EmptyStruct {},
+	}
+	assert!(parse::<MyEnum>(r#"{"type":"empty_tuple"}"#) == MyEnum::EmptyTuple());
+	assert!(parse::<MyEnum>(r#"{"type":"empty_tuple","empty_tuple":null}"#) == MyEnum::EmptyTuple());
+	assert!(parse::<MyEnum>(r#"{"type":"empty_struct"}"#) == MyEnum::EmptyStruct {});
+	// Content written without `#[serde(empty_as_unit)]` is still accepted.
+	assert!(parse::<MyEnum>(r#"{"type":"empty_tuple","empty_tuple":[]}"#) == MyEnum::EmptyTuple());
+	assert!(parse::<MyEnum>(r#"{"type":"empty_tuple","empty_tuple":{}}"#) == MyEnum::EmptyTuple());
+	assert!(parse::<MyEnum>(r#"{"type":"empty_struct","empty_struct":{}}"#) == MyEnum::EmptyStruct {});
+	assert!(parse::<MyEnum>(r#"{"type":"empty_struct","empty_struct":[]}"#) == MyEnum::EmptyStruct {});
+	assert!(parse::<MyEnum>(r#"{"type":"empty_struct","empty_struct":null}"#) == MyEnum::EmptyStruct {});
+	assert!(parse_err::<MyEnum>(r#"{"type":"empty_tuple","empty_tuple":[1]}"#).contains("invalid length 1"));
+	assert!(parse_err::<MyEnum>(r#"{"type":"empty_struct","empty_struct":{"a":1}}"#).contains("invalid length 1"));
+
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type", empty_as_unit, unit_content = "empty")]
+	enum EmptyUnit {
+		EmptyTuple(),
+	}
+	assert!(parse::<EmptyUnit>(r#"{"type":"empty_tuple","empty_tuple":{}}"#) == EmptyUnit::EmptyTuple());
+	assert!(parse_err::<EmptyUnit>(r#"{"type":"empty_tuple"}"#).contains("missing field `empty_tuple`"));
+}
+
+#[test]
+fn deserialize_enum_field_default() {
+	fn default_name() -> String {
+		"Rex".into()
+	}
+
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	enum MyEnum {
+		Tuple(u8, #[serde(default)] u8),
+		Struct {
+			#[serde(default = "default_name")]
+			name: String,
+			#[serde(default)]
+			age: u8,
+		},
+	}
+	assert!(parse::<MyEnum>(r#"{"type":"tuple","tuple":[3]}"#) == MyEnum::Tuple(3, 0));
+	assert!(parse::<MyEnum>(r#"{"type":"tuple","tuple":[3,4]}"#) == MyEnum::Tuple(3, 4));
+	assert!(
+		parse::<MyEnum>(r#"{"type":"struct","struct":{}}"#)
+			== MyEnum::Struct {
+				name: "Rex".into(),
+				age: 0
+			}
+	);
+	assert!(
+		parse::<MyEnum>(r#"{"type":"struct","struct":{"name":"Scrappy","age":3}}"#)
+			== MyEnum::Struct {
+				name: "Scrappy".into(),
+				age: 3
+			}
+	);
+	assert!(parse_err::<MyEnum>(r#"{"type":"tuple","tuple":[]}"#).contains("invalid length 0"));
+}
+
+#[test]
+fn deserialize_enum_skip() {
+	fn default_hits() -> u32 {
+		7
+	}
+
+	#[derive(Debug, Default, PartialEq)]
+	struct Cache(u32);
+
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	enum MyEnum<C> {
+		Struct {
+			id: u32,
+			#[serde(skip)]
+			cache: C,
+			#[serde(skip_deserializing, default = "default_hits")]
+			hits: u32,
+		},
+		Tuple(u8, #[serde(skip_deserializing)] C),
+	}
+	assert!(
+		parse::<MyEnum<Cache>>(r#"{"type":"struct","struct":{"id":1,"hits":2}}"#)
+			== MyEnum::Struct {
+				id: 1,
+				cache: Cache(0),
+				hits: 7
+			}
+	);
+	assert!(parse::<MyEnum<Cache>>(r#"{"type":"tuple","tuple":[1]}"#) == MyEnum::Tuple(1, Cache(0)));
+}
+
+#[test]
+fn deserialize_enum_variant_default() {
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	enum MyEnum {
+		#[serde(default)]
+		Tuple(u8, String),
+		#[serde(default)]
+		Struct {
+			field_a: String,
+			field_b: u8,
+		},
+		Required {
+			field_a: String,
+		},
+	}
+	assert!(parse::<MyEnum>(r#"{"type":"tuple"}"#) == MyEnum::Tuple(0, String::new()));
+	assert!(parse::<MyEnum>(r#"{"type":"tuple","tuple":[3]}"#) == MyEnum::Tuple(3, String::new()));
+	assert!(
+		parse::<MyEnum>(r#"{"type":"struct"}"#)
+			== MyEnum::Struct {
+				field_a: String::new(),
+				field_b: 0
+			}
+	);
+	assert!(
+		parse::<MyEnum>(r#"{"type":"struct","struct":{"field_b":7}}"#)
+			== MyEnum::Struct {
+				field_a: String::new(),
+				field_b: 7
+			}
+	);
+	assert!(parse_err::<MyEnum>(r#"{"type":"required"}"#).contains("missing field `required`"));
+	assert!(parse_err::<MyEnum>(r#"{"type":"required","required":{}}"#).contains("missing field `field_a`"));
+}
+
+#[test]
+fn deserialize_enum_default() {
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type", default)]
+	enum MyEnum<T> {
+		Unit,
+		NewType(T),
+		Struct { items: Vec<T>, count: u8 },
+	}
+	assert!(parse::<MyEnum<u8>>(r#"{"type":"unit"}"#) == MyEnum::Unit);
+	assert!(parse::<MyEnum<u8>>(r#"{"type":"new_type"}"#) == MyEnum::NewType(0));
+	assert!(parse::<MyEnum<u8>>(r#"{"type":"new_type","new_type":5}"#) == MyEnum::NewType(5));
+	assert!(
+		parse::<MyEnum<u8>>(r#"{"type":"struct","struct":{"count":2}}"#)
+			== MyEnum::Struct {
+				items: Vec::new(),
+				count: 2
+			}
+	);
+}
+
+#[test]
+fn deserialize_enum_with() {
+	mod hex {
+		#[allow(unused)]
+		pub fn serialize<S: serde::Serializer>(_value: &u32, _serializer: S) -> Result<S::Ok, S::Error> {
+			unimplemented!()
+		}
+
+		pub fn deserialize<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+			let value: &str = serde::Deserialize::deserialize(deserializer)?;
+			u32::from_str_radix(value, 16).map_err(serde::de::Error::custom)
+		}
+	}
+
+	fn deserialize_len<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+		let len: usize = serde::Deserialize::deserialize(deserializer)?;
+		Ok(vec![0; len])
+	}
+
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	enum MyEnum {
+		Struct {
+			#[serde(with = "hex")]
+			id: u32,
+			#[serde(deserialize_with = "deserialize_len")]
+			data: Vec<u8>,
+		},
+		Tuple(u8, #[serde(with = "hex")] u32),
+	}
+	assert!(
+		parse::<MyEnum>(r#"{"type":"struct","struct":{"id":"ff","data":3}}"#)
+			== MyEnum::Struct {
+				id: 255,
+				data: vec![0, 0, 0]
+			}
+	);
+	assert!(parse::<MyEnum>(r#"{"type":"tuple","tuple":[1,"10"]}"#) == MyEnum::Tuple(1, 16));
+	assert!(parse_err::<MyEnum>(r#"{"type":"tuple","tuple":[1,"xyz"]}"#).contains("invalid digit"));
+}
+
+#[test]
+fn deserialize_enum_tag_not_first_128_bit() {
+	use serde::de::value::{Error, MapDeserializer};
+	use serde::de::IntoDeserializer;
+
+	// `serde_json` never produces 128 bit integers for `deserialize_any()`, so use a minimal self-describing format.
+	enum Value {
+		Str(&'static str),
+		U128(u128),
+		I128(i128),
+	}
+
+	impl<'de> serde::Deserializer<'de> for Value {
+		type Error = Error;
+
+		fn deserialize_any<V: serde::de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+			match self {
+				Self::Str(x) => visitor.visit_borrowed_str(x),
+				Self::U128(x) => visitor.visit_u128(x),
+				Self::I128(x) => visitor.visit_i128(x),
+			}
+		}
+
+		serde::forward_to_deserialize_any! {
+			bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+			bytes byte_buf option unit unit_struct newtype_struct seq tuple
+			tuple_struct map struct enum identifier ignored_any
+		}
+	}
+
+	impl IntoDeserializer<'_, Error> for Value {
+		type Deserializer = Self;
+
+		fn into_deserializer(self) -> Self {
+			self
+		}
+	}
+
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize)]
+	#[serde(tag = "type")]
+	enum Big {
+		Unsigned(u128),
+		Signed(i128),
+	}
+
+	let parse = |entries: [(&'static str, Value); 2]| -> Result<Big, Error> {
+		serde::Deserialize::deserialize(MapDeserializer::new(entries.into_iter()))
+	};
+	let_assert!(Ok(value) = parse([("Unsigned", Value::U128(u128::MAX)), ("type", Value::Str("Unsigned"))]));
+	assert!(value == Big::Unsigned(u128::MAX));
+	let_assert!(Ok(value) = parse([("Signed", Value::I128(i128::MIN)), ("type", Value::Str("Signed"))]));
+	assert!(value == Big::Signed(i128::MIN));
+}
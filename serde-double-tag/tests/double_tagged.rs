@@ -0,0 +1,102 @@
+use assert2::assert;
+use serde_double_tag::{DoubleTagged, VariantInfo};
+
+#[derive(serde_double_tag::Serialize, serde_double_tag::Deserialize, serde_double_tag::DoubleTagged)]
+#[serde(tag = "species")]
+#[serde(rename_all = "snake_case")]
+#[serde(content = "{tag}_data")]
+#[allow(unused)]
+enum Friend {
+	Human {
+		name: String,
+	},
+	#[serde(alias = "doggo")]
+	Dog(String),
+	#[serde(content = "feline")]
+	Cat(String),
+	Nobody,
+}
+
+#[test]
+fn double_tagged_variants() {
+	assert!(Friend::TAG_FIELD == "species");
+	assert!(
+		Friend::VARIANTS
+			== [
+				VariantInfo {
+					name: "Human",
+					tag_value: "human",
+					content_key: Some("human_data"),
+					has_content: true,
+				},
+				VariantInfo {
+					name: "Dog",
+					tag_value: "dog",
+					content_key: Some("dog_data"),
+					has_content: true,
+				},
+				VariantInfo {
+					name: "Cat",
+					tag_value: "cat",
+					content_key: Some("feline"),
+					has_content: true,
+				},
+				VariantInfo {
+					name: "Nobody",
+					tag_value: "nobody",
+					content_key: Some("nobody_data"),
+					has_content: false,
+				},
+			]
+	);
+}
+
+#[test]
+fn double_tagged_active_variant() {
+	let friend = Friend::Human { name: "Zohan".into() };
+	assert!(friend.tag_value() == "human");
+	assert!(friend.content_key() == Some("human_data"));
+
+	let friend = Friend::Cat("Tom".into());
+	assert!(friend.tag_value() == "cat");
+	assert!(friend.content_key() == Some("feline"));
+
+	assert!(Friend::Nobody.tag_value() == "nobody");
+	assert!(Friend::Nobody.content_key() == None);
+}
+
+#[test]
+fn double_tagged_tag_values() {
+	#[derive(serde_double_tag::DoubleTagged)]
+	#[serde(tag = "type")]
+	#[allow(unused)]
+	enum Shape {
+		#[serde(tag_value = 1)]
+		Circle(f64),
+		#[serde(tag_value = false)]
+		Square(f64),
+		#[serde(other)]
+		Unknown,
+	}
+
+	assert!(Shape::Circle(1.0).tag_value() == "1");
+	assert!(Shape::Circle(1.0).content_key() == Some("Circle"));
+	assert!(Shape::Square(1.0).tag_value() == "false");
+	assert!(Shape::Unknown.tag_value() == "Unknown");
+	assert!(Shape::VARIANTS[2].content_key == None);
+}
+
+#[test]
+fn double_tagged_unit_content() {
+	#[derive(serde_double_tag::Serialize, serde_double_tag::DoubleTagged)]
+	#[serde(tag = "type", unit_content = "null")]
+	#[allow(unused)]
+	enum Shape {
+		Circle(f64),
+		Point,
+	}
+
+	assert!(Shape::Point.content_key() == Some("Point"));
+	assert!(!Shape::VARIANTS[1].has_content);
+	assert!(serde_json::to_string(&Shape::Point).unwrap() == r#"{"type":"Point","Point":null}"#);
+}
@@ -0,0 +1,215 @@
+use assert2::{assert, let_assert};
+
+#[track_caller]
+fn parse<'a, T: serde::Deserialize<'a> + std::fmt::Debug>(data: &'a str) -> T {
+	let_assert!(Ok(value) = serde_json::from_str(data));
+	value
+}
+
+#[track_caller]
+fn parse_err<'a, T: serde::Deserialize<'a> + std::fmt::Debug>(data: &'a str) -> String {
+	let_assert!(Err(e) = serde_json::from_str::<T>(data));
+	e.to_string()
+}
+
+#[track_caller]
+fn json<T: serde::Serialize>(value: T) -> String {
+	let_assert!(Ok(json) = serde_json::to_string(&value));
+	json
+}
+
+#[track_caller]
+fn schema<T: schemars::JsonSchema>() -> serde_json::Value {
+	let schema = schemars::gen::SchemaGenerator::default().into_root_schema_for::<T>();
+	serde_json::to_value(schema.schema).unwrap()
+}
+
+#[derive(
+	Debug, PartialEq, serde_double_tag::Serialize, serde_double_tag::Deserialize, serde_double_tag::JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+#[serde(tag = "species")]
+enum Friend {
+	Human { name: String },
+	Dog { name: String, color: String },
+	Nobody,
+}
+
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize, schemars::JsonSchema)]
+struct Record {
+	id: u32,
+	#[serde(flatten)]
+	friend: Friend,
+	note: String,
+}
+
+#[test]
+fn flatten_serialize() {
+	let record = Record {
+		id: 1,
+		friend: Friend::Human { name: "Zohan".into() },
+		note: "hairdresser".into(),
+	};
+	assert!(json(&record) == r#"{"id":1,"species":"human","human":{"name":"Zohan"},"note":"hairdresser"}"#);
+
+	let record = Record {
+		id: 2,
+		friend: Friend::Nobody,
+		note: "lonely".into(),
+	};
+	assert!(json(&record) == r#"{"id":2,"species":"nobody","note":"lonely"}"#);
+}
+
+#[test]
+fn flatten_deserialize() {
+	let expected = Record {
+		id: 1,
+		friend: Friend::Dog {
+			name: "Scrappy".into(),
+			color: "white".into(),
+		},
+		note: "good boy".into(),
+	};
+	assert!(
+		parse::<Record>(r#"{"id":1,"species":"dog","dog":{"name":"Scrappy","color":"white"},"note":"good boy"}"#)
+			== expected
+	);
+	assert!(
+		parse::<Record>(r#"{"dog":{"name":"Scrappy","color":"white"},"note":"good boy","species":"dog","id":1}"#)
+			== expected
+	);
+	assert!(
+		parse::<Record>(
+			r#"{"note":"good boy","human":{"name":"Zohan"},"dog":{"name":"Scrappy","color":"white"},"id":1,"species":"dog"}"#
+		) == expected
+	);
+
+	let error = parse_err::<Record>(r#"{"id":1,"dog":{"name":"Scrappy","color":"white"},"note":"good boy"}"#);
+	assert!(error.contains("missing field `species`"));
+	let error = parse_err::<Record>(r#"{"id":1,"species":"dog","note":"good boy"}"#);
+	assert!(error.contains("missing field `dog`"));
+}
+
+#[test]
+fn flatten_multiple() {
+	#[derive(Debug, PartialEq, serde_double_tag::Serialize, serde_double_tag::Deserialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "shape")]
+	enum Shape {
+		Circle { radius: f64 },
+		Square { side: f64 },
+	}
+
+	#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+	struct Drawing {
+		#[serde(flatten)]
+		friend: Friend,
+		#[serde(flatten)]
+		shape: Shape,
+	}
+
+	let data = r#"{"circle":{"radius":1.0},"species":"human","human":{"name":"Zohan"},"shape":"circle"}"#;
+	let drawing = parse::<Drawing>(data);
+	assert!(drawing.friend == Friend::Human { name: "Zohan".into() });
+	assert!(drawing.shape == Shape::Circle { radius: 1.0 });
+	assert!(
+		json(&drawing) == r#"{"species":"human","human":{"name":"Zohan"},"shape":"circle","circle":{"radius":1.0}}"#
+	);
+}
+
+#[test]
+fn flatten_schema() {
+	let value = schema::<Record>();
+	assert!(value["required"] == serde_json::json!(["id", "note", "species"]));
+	assert!(value["properties"]["id"]["type"] == "integer");
+	assert!(value["properties"]["note"]["type"] == "string");
+	assert!(value["properties"]["species"]["enum"] == serde_json::json!(["human", "dog", "nobody"]));
+	assert!(value["allOf"][0]["if"]["properties"]["species"]["const"] == "human");
+	assert!(value["allOf"][0]["then"]["required"] == serde_json::json!(["human", "species"]));
+	assert!(value["allOf"][1]["then"]["required"] == serde_json::json!(["dog", "species"]));
+}
+
+#[test]
+fn flatten_multiple_schema() {
+	#[derive(serde_double_tag::Serialize, serde_double_tag::JsonSchema)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "shape")]
+	#[allow(unused)]
+	enum Shape {
+		Circle { radius: f64 },
+		Square { side: f64 },
+	}
+
+	#[derive(serde::Serialize, schemars::JsonSchema)]
+	#[allow(unused)]
+	struct Drawing {
+		#[serde(flatten)]
+		friend: Friend,
+		#[serde(flatten)]
+		shape: Shape,
+	}
+
+	// The tag fields of both enums are kept, but `schemars` only keeps the variant subschemas of the first enum.
+	let value = schema::<Drawing>();
+	assert!(value["required"] == serde_json::json!(["shape", "species"]));
+	assert!(value["properties"]["species"]["enum"] == serde_json::json!(["human", "dog", "nobody"]));
+	assert!(value["properties"]["shape"]["enum"] == serde_json::json!(["circle", "square"]));
+	assert!(value["allOf"][0]["if"]["properties"]["species"]["const"] == "human");
+	assert!(value["allOf"].as_array().unwrap().len() == 2);
+}
+
+#[test]
+fn flatten_schema_hidden_api() {
+	use schemars::JsonSchema;
+
+	#[derive(serde_double_tag::JsonSchema)]
+	#[serde(tag = "shape", deny_unknown_fields)]
+	#[allow(unused)]
+	enum Shape {
+		Circle { radius: f64 },
+	}
+
+	// The `JsonSchema` derive relies on these hidden functions of `schemars`, which are not covered by semver.
+	// This test stops compiling or fails if a new `schemars` release changes them.
+	let mut generator = schemars::gen::SchemaGenerator::default();
+	assert!(!Shape::_schemars_private_is_option());
+	let schema = serde_json::to_value(Shape::json_schema(&mut generator)).unwrap();
+	assert!(schema["then"]["additionalProperties"] == false);
+	let schema = serde_json::to_value(Shape::_schemars_private_non_optional_json_schema(&mut generator)).unwrap();
+	assert!(schema["then"]["additionalProperties"].is_null());
+}
+
+#[test]
+fn flatten_deny_unknown_fields() {
+	#[derive(Debug, PartialEq, serde_double_tag::Deserialize, serde_double_tag::JsonSchema)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "shape", deny_unknown_fields)]
+	enum Shape {
+		Circle { radius: f64 },
+		Square { side: f64 },
+	}
+
+	#[derive(Debug, PartialEq, serde::Deserialize, schemars::JsonSchema)]
+	struct Drawing {
+		id: u32,
+		#[serde(flatten)]
+		shape: Shape,
+	}
+
+	// The fields of the parent struct are not seen by the enum.
+	let drawing = parse::<Drawing>(r#"{"id":1,"circle":{"radius":1.0},"shape":"circle"}"#);
+	assert!(drawing.shape == Shape::Circle { radius: 1.0 });
+	let error = parse_err::<Drawing>(r#"{"id":1,"circle":{"radius":1.0},"shape":"circle","extra":1}"#);
+	assert!(error.contains("unknown field `extra`"));
+
+	// So the schema of the variants must allow the fields of the parent struct.
+	let value = schema::<Drawing>();
+	assert!(value["required"] == serde_json::json!(["id", "shape"]));
+	assert!(value["allOf"][0]["then"]["required"] == serde_json::json!(["circle", "shape"]));
+	assert!(value["allOf"][0]["then"].get("additionalProperties").is_none());
+	assert!(value["allOf"][1]["then"].get("additionalProperties").is_none());
+
+	// Without flatten, the schema still rejects unknown fields.
+	let value = schema::<Shape>();
+	assert!(value["allOf"][0]["then"]["additionalProperties"] == false);
+}
@@ -0,0 +1,465 @@
+use assert2::assert;
+
+#[track_caller]
+fn schema<T: schemars::JsonSchema>() -> serde_json::Value {
+	let schema = schemars::gen::SchemaGenerator::default().into_root_schema_for::<T>();
+	serde_json::to_value(schema.schema).unwrap()
+}
+
+#[test]
+fn schema_tag() {
+	#[derive(serde_double_tag::JsonSchema)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	#[allow(unused)]
+	enum MyEnum {
+		Unit,
+		NewType(String),
+	}
+	let schema = schema::<MyEnum>();
+	assert!(schema["properties"]["type"] == serde_json::json!({ "type": "string", "enum": ["unit", "new_type"] }));
+	assert!(schema["required"] == serde_json::json!(["type"]));
+}
+
+#[test]
+fn schema_tag_other() {
+	#[derive(serde_double_tag::JsonSchema)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	#[allow(unused)]
+	enum MyEnum {
+		NewType(String),
+		#[serde(other)]
+		Unknown,
+	}
+	let schema = schema::<MyEnum>();
+	assert!(schema["properties"]["type"] == serde_json::json!({ "type": "string" }));
+}
+
+#[test]
+fn schema_alias() {
+	#[derive(serde_double_tag::JsonSchema)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	#[allow(unused)]
+	enum MyEnum {
+		#[serde(alias = "old_name")]
+		NewType(String),
+	}
+	let schema = schema::<MyEnum>();
+	assert!(schema["properties"]["type"] == serde_json::json!({ "type": "string", "enum": ["new_type", "old_name"] }));
+	assert!(
+		schema["if"]["properties"]["type"] == serde_json::json!({ "type": "string", "enum": ["new_type", "old_name"] })
+	);
+	assert!(schema["then"]["required"] == serde_json::json!(["type"]));
+	assert!(schema["then"]["properties"]["new_type"] == serde_json::json!({ "type": "string" }));
+	assert!(schema["then"]["properties"]["old_name"] == serde_json::json!({ "type": "string" }));
+	assert!(schema["then"]["anyOf"][0]["required"] == serde_json::json!(["new_type"]));
+	assert!(schema["then"]["anyOf"][1]["required"] == serde_json::json!(["old_name"]));
+}
+
+#[test]
+fn schema_content_name() {
+	#[derive(serde_double_tag::JsonSchema)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	#[allow(unused)]
+	enum MyEnum {
+		#[serde(content = "dog_data")]
+		Dog(String),
+	}
+	let schema = schema::<MyEnum>();
+	assert!(schema["if"]["properties"]["type"] == serde_json::json!({ "type": "string", "const": "dog" }));
+	assert!(schema["then"]["required"] == serde_json::json!(["dog_data", "type"]));
+	assert!(schema["then"]["properties"]["dog_data"] == serde_json::json!({ "type": "string" }));
+}
+
+#[test]
+fn schema_rename_all_content() {
+	#[derive(serde_double_tag::JsonSchema)]
+	#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+	#[serde(rename_all_content = "camelCase")]
+	#[serde(tag = "type")]
+	#[allow(unused)]
+	enum MyEnum {
+		NewType(String),
+	}
+	let schema = schema::<MyEnum>();
+	assert!(schema["if"]["properties"]["type"] == serde_json::json!({ "type": "string", "const": "NEW_TYPE" }));
+	assert!(schema["then"]["properties"]["newType"] == serde_json::json!({ "type": "string" }));
+}
+
+#[test]
+fn schema_tag_value() {
+	#[derive(serde_double_tag::JsonSchema)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "kind")]
+	#[serde(discriminant_as_tag)]
+	#[allow(unused)]
+	#[repr(u8)]
+	enum MyEnum {
+		Dog(String) = 1,
+		Cat(String),
+	}
+	let schema = schema::<MyEnum>();
+	assert!(schema["properties"]["kind"] == serde_json::json!({ "type": "integer", "enum": [1, 2] }));
+	assert!(schema["allOf"][0]["if"]["properties"]["kind"] == serde_json::json!({ "type": "integer", "const": 1 }));
+	assert!(schema["allOf"][0]["then"]["properties"]["dog"] == serde_json::json!({ "type": "string" }));
+}
+
+#[test]
+fn schema_tag_value_mixed() {
+	#[derive(serde_double_tag::JsonSchema)]
+	#[serde(tag = "kind")]
+	#[allow(unused)]
+	enum MyEnum {
+		#[serde(tag_value = 1)]
+		Dog(String),
+		Cat(String),
+	}
+	let schema = schema::<MyEnum>();
+	assert!(schema["properties"]["kind"] == serde_json::json!({ "type": ["integer", "string"], "enum": [1, "Cat"] }));
+}
+
+#[test]
+fn schema_infer_tag() {
+	#[derive(serde_double_tag::JsonSchema)]
+	#[serde(tag = "type")]
+	#[serde(infer_tag)]
+	#[allow(unused)]
+	enum MyEnum {
+		NewType(String),
+	}
+	let schema = schema::<MyEnum>();
+	assert!(schema.get("required").is_none());
+}
+
+#[test]
+fn schema_inactive_content() {
+	#[derive(serde_double_tag::JsonSchema)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	#[serde(deny_unknown_fields)]
+	#[allow(unused)]
+	enum DenyAll {
+		Dog(String),
+		Cat(String),
+	}
+	let value = schema::<DenyAll>();
+	assert!(value["allOf"][0]["then"]["additionalProperties"] == false);
+	assert!(value["allOf"][0]["then"]["properties"].get("cat").is_none());
+
+	#[derive(serde_double_tag::JsonSchema)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	#[serde(deny_unknown_fields, inactive_content = "allow")]
+	#[allow(unused)]
+	enum AllowInactive {
+		Dog(String),
+		Cat(String),
+	}
+	let value = schema::<AllowInactive>();
+	assert!(value["allOf"][0]["then"]["additionalProperties"] == false);
+	assert!(value["allOf"][0]["then"]["properties"]["cat"] == true);
+	assert!(value["allOf"][0]["then"]["required"] == serde_json::json!(["dog", "type"]));
+
+	#[derive(serde_double_tag::JsonSchema)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	#[serde(inactive_content = "deny")]
+	#[allow(unused)]
+	enum DenyInactive {
+		Dog(String),
+		Cat(String),
+	}
+	let value = schema::<DenyInactive>();
+	assert!(value["allOf"][0]["then"].get("additionalProperties").is_none());
+	assert!(value["allOf"][0]["then"]["properties"]["cat"] == false);
+}
+
+#[test]
+fn schema_all_variants() {
+	#[derive(serde_double_tag::AllVariants)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "species")]
+	#[serde(deny_unknown_fields)]
+	#[serde(derive_json_schema)]
+	#[allow(unused)]
+	enum Friend {
+		Human { name: String },
+		Dog(String),
+		Nobody,
+	}
+
+	let value = schema::<FriendAllVariants>();
+	assert!(value["required"] == serde_json::json!(["species"]));
+	assert!(value["additionalProperties"] == false);
+	assert!(value["properties"]["dog"]["anyOf"][0]["$ref"] == "#/definitions/FriendDogData");
+	assert!(value["properties"]["human"]["anyOf"][0]["$ref"] == "#/definitions/FriendHumanData");
+}
+
+#[test]
+fn schema_variant_structs() {
+	#[derive(serde_double_tag::VariantStructs)]
+	#[serde(rename_all_fields = "camelCase")]
+	#[serde(tag = "species")]
+	#[serde(deny_unknown_fields)]
+	#[serde(derive_json_schema)]
+	#[allow(unused)]
+	enum Friend {
+		Human { favorite_food: String },
+		Dog(String),
+	}
+
+	let value = schema::<FriendHuman>();
+	assert!(value["required"] == serde_json::json!(["favoriteFood"]));
+	assert!(value["additionalProperties"] == false);
+	assert!(value["properties"]["favoriteFood"]["type"] == "string");
+}
+
+#[test]
+fn schema_null_inactive() {
+	#[derive(serde_double_tag::JsonSchema)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	#[serde(deny_unknown_fields, null_inactive)]
+	#[allow(unused)]
+	enum MyEnum {
+		Dog(String),
+		Cat(String),
+	}
+	let value = schema::<MyEnum>();
+	assert!(value["allOf"][0]["then"]["additionalProperties"] == false);
+	assert!(value["allOf"][0]["then"]["properties"]["cat"] == serde_json::json!({"type": "null"}));
+	assert!(value["allOf"][0]["then"]["required"] == serde_json::json!(["dog", "type"]));
+}
+
+#[test]
+fn schema_unit_content() {
+	#[derive(serde_double_tag::JsonSchema)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type", unit_content = "null")]
+	#[allow(unused)]
+	enum NullUnit {
+		Unit,
+	}
+	let value = schema::<NullUnit>();
+	assert!(value["then"]["properties"]["unit"] == serde_json::json!({"type": "null"}));
+	assert!(value["then"]["required"] == serde_json::json!(["type", "unit"]));
+
+	#[derive(serde_double_tag::JsonSchema)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type", unit_content = "empty")]
+	#[allow(unused)]
+	enum EmptyUnit {
+		Unit,
+	}
+	let value = schema::<EmptyUnit>();
+	assert!(
+		value["then"]["properties"]["unit"] == serde_json::json!({"type": "object", "additionalProperties": false})
+	);
+	assert!(value["then"]["required"] == serde_json::json!(["type", "unit"]));
+
+	#[derive(serde_double_tag::JsonSchema)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type", unit_content = "omit")]
+	#[allow(unused)]
+	enum OmitUnit {
+		Unit,
+	}
+	let value = schema::<OmitUnit>();
+	assert!(value["then"]["properties"]["unit"] == false);
+	assert!(value["then"]["required"] == serde_json::json!(["type"]));
+}
+
+#[test]
+fn schema_empty_as_unit() {
+	#[derive(serde_double_tag::JsonSchema)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type", empty_as_unit)]
+	#[allow(unused)]
+	enum MyEnum {
+		EmptyTuple(),
+		EmptyStruct {},
+		NewType(String),
+	}
+	let value = schema::<MyEnum>();
+	assert!(value["then"]["properties"]["new_type"]["type"] == "string");
+	assert!(value.get("allOf").is_none());
+
+	#[derive(serde_double_tag::JsonSchema)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type", empty_as_unit, unit_content = "omit")]
+	#[allow(unused)]
+	enum OmitUnit {
+		EmptyTuple(),
+	}
+	let value = schema::<OmitUnit>();
+	assert!(value["then"]["properties"]["empty_tuple"] == false);
+	assert!(value["then"]["required"] == serde_json::json!(["type"]));
+}
+
+#[test]
+fn schema_field_default() {
+	fn default_name() -> String {
+		"Rex".into()
+	}
+
+	#[derive(serde_double_tag::JsonSchema)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	#[allow(unused)]
+	enum Struct {
+		Struct {
+			#[serde(default = "default_name")]
+			name: String,
+			#[serde(default)]
+			age: u8,
+			color: String,
+		},
+	}
+	let struct_schema = schema::<Struct>();
+	let content = &struct_schema["then"]["properties"]["struct"];
+	assert!(content["properties"]["name"] == serde_json::json!({ "type": "string", "default": "Rex" }));
+	assert!(content["properties"]["age"]["default"] == 0);
+	assert!(content["required"] == serde_json::json!(["color"]));
+
+	#[derive(serde_double_tag::JsonSchema)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	#[allow(unused)]
+	enum Tuple {
+		Tuple(u8, #[serde(default)] u8),
+	}
+	let tuple_schema = schema::<Tuple>();
+	let content = &tuple_schema["then"]["properties"]["tuple"];
+	assert!(content["minItems"] == 1);
+	assert!(content["maxItems"] == 2);
+	assert!(content["items"][1]["default"] == 0);
+}
+
+#[test]
+fn schema_variant_default() {
+	#[derive(serde_double_tag::JsonSchema)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	#[allow(unused)]
+	enum Struct {
+		#[serde(default)]
+		Struct { name: String, age: u8 },
+	}
+	let struct_schema = schema::<Struct>();
+	assert!(struct_schema["then"]["required"] == serde_json::json!(["type"]));
+	assert!(struct_schema["then"]["properties"]["struct"].get("required") == None);
+
+	#[derive(serde_double_tag::JsonSchema)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type", default)]
+	#[allow(unused)]
+	enum Tuple {
+		#[serde(alias = "pair")]
+		Tuple(u8, u8),
+	}
+	let tuple_schema = schema::<Tuple>();
+	assert!(tuple_schema["then"]["required"] == serde_json::json!(["type"]));
+	assert!(tuple_schema["then"].get("anyOf") == None);
+	assert!(tuple_schema["then"]["properties"]["tuple"]["minItems"] == 0);
+}
+
+#[test]
+fn schema_skip_serializing_if() {
+	#[derive(serde_double_tag::JsonSchema)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	#[allow(unused)]
+	enum MyEnum {
+		Struct {
+			#[serde(skip_serializing_if = "Vec::is_empty")]
+			items: Vec<u8>,
+			name: String,
+		},
+	}
+	let schema = schema::<MyEnum>();
+	let content = &schema["then"]["properties"]["struct"];
+	assert!(content["properties"]["items"]["type"] == "array");
+	assert!(content["required"] == serde_json::json!(["name"]));
+}
+
+#[test]
+fn schema_skip() {
+	struct Cache;
+
+	#[derive(serde_double_tag::JsonSchema)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	#[allow(unused)]
+	enum MyEnum<C> {
+		Struct {
+			id: u32,
+			#[serde(skip)]
+			cache: C,
+			#[serde(skip_deserializing)]
+			hits: u32,
+		},
+		Tuple(u8, #[serde(skip_serializing)] C, String),
+	}
+	let enum_schema = schema::<MyEnum<Cache>>();
+	let content = &enum_schema["allOf"][0]["then"]["properties"]["struct"];
+	assert!(
+		content["properties"] == serde_json::json!({ "id": { "type": "integer", "format": "uint32", "minimum": 0.0 } })
+	);
+	assert!(content["required"] == serde_json::json!(["id"]));
+	let content = &enum_schema["allOf"][1]["then"]["properties"]["tuple"];
+	assert!(
+		content["items"]
+			== serde_json::json!([{ "type": "integer", "format": "uint8", "minimum": 0.0 }, { "type": "string" }])
+	);
+	assert!(content["minItems"] == 2);
+	assert!(content["maxItems"] == 2);
+}
+
+#[test]
+fn schema_with() {
+	mod hex {
+		pub fn serialize<S: serde::Serializer>(value: &u32, serializer: S) -> Result<S::Ok, S::Error> {
+			serializer.serialize_str(&format!("{value:x}"))
+		}
+
+		pub fn deserialize<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+			let value: &str = serde::Deserialize::deserialize(deserializer)?;
+			u32::from_str_radix(value, 16).map_err(serde::de::Error::custom)
+		}
+	}
+
+	fn hex_schema(_generator: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+		schemars::schema::SchemaObject {
+			instance_type: Some(schemars::schema::InstanceType::String.into()),
+			format: Some("hex".into()),
+			..Default::default()
+		}
+		.into()
+	}
+
+	#[derive(serde_double_tag::JsonSchema, serde_double_tag::VariantStructs)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	#[serde(derive_json_schema)]
+	#[allow(unused)]
+	enum MyEnum {
+		Struct {
+			#[serde(with = "hex")]
+			#[schemars(schema_with = "hex_schema")]
+			id: u32,
+			#[schemars(with = "Option<String>")]
+			name: String,
+		},
+	}
+	let enum_schema = schema::<MyEnum>();
+	let content = &enum_schema["then"]["properties"]["struct"];
+	assert!(content["properties"]["id"] == serde_json::json!({ "type": "string", "format": "hex" }));
+	assert!(content["properties"]["name"] == serde_json::json!({ "type": ["string", "null"] }));
+	assert!(content["required"] == serde_json::json!(["id"]));
+
+	let payload = schema::<MyEnumStruct>();
+	assert!(payload["properties"]["id"] == serde_json::json!({ "type": "string", "format": "hex" }));
+}
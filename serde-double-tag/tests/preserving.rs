@@ -0,0 +1,242 @@
+use assert2::{assert, let_assert};
+use serde_double_tag::Preserving;
+
+#[track_caller]
+fn parse<'a, T: serde::Deserialize<'a> + std::fmt::Debug>(data: &'a str) -> T {
+	let_assert!(Ok(value) = serde_json::from_str(data));
+	value
+}
+
+#[track_caller]
+fn json<T: serde::Serialize>(value: T) -> String {
+	let_assert!(Ok(json) = serde_json::to_string(&value));
+	json
+}
+
+#[derive(Debug, PartialEq, serde_double_tag::Serialize, serde_double_tag::Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[serde(tag = "species")]
+enum Friend {
+	Human {
+		name: String,
+	},
+	#[serde(alias = "doggo")]
+	Dog {
+		name: String,
+	},
+	Cat(String),
+	Unknown,
+}
+
+#[test]
+fn preserve_inactive_content() {
+	let friend: Preserving<Friend> =
+		parse(r#"{"species":"dog","human":{"name":"Zohan"},"dog":{"name":"Scrappy"},"extra":1,"cat":"Tom"}"#);
+	assert!(*friend == Friend::Dog { name: "Scrappy".into() });
+	assert!(json(&friend) == r#"{"species":"dog","dog":{"name":"Scrappy"},"human":{"name":"Zohan"},"cat":"Tom"}"#);
+}
+
+#[test]
+fn preserve_previously_active_content() {
+	let mut friend: Preserving<Friend> = parse(r#"{"species":"dog","dog":{"name":"Scrappy"}}"#);
+	*friend = Friend::Human { name: "Zohan".into() };
+	assert!(json(&friend) == r#"{"species":"human","human":{"name":"Zohan"},"dog":{"name":"Scrappy"}}"#);
+
+	let mut friend: Preserving<Friend> = parse(&json(&friend));
+	*friend = Friend::Dog { name: "Scooby".into() };
+	assert!(json(&friend) == r#"{"species":"dog","dog":{"name":"Scooby"},"human":{"name":"Zohan"}}"#);
+
+	*friend = Friend::Unknown;
+	assert!(json(&friend) == r#"{"species":"unknown","human":{"name":"Zohan"},"dog":{"name":"Scrappy"}}"#);
+}
+
+#[test]
+fn preserve_aliased_content() {
+	let mut friend: Preserving<Friend> =
+		parse(r#"{"species":"human","human":{"name":"Zohan"},"doggo":{"name":"Scrappy"}}"#);
+	assert!(json(&friend) == r#"{"species":"human","human":{"name":"Zohan"},"doggo":{"name":"Scrappy"}}"#);
+
+	// The aliased content field belongs to the active variant now, so it is replaced.
+	*friend = Friend::Dog { name: "Scooby".into() };
+	assert!(json(&friend) == r#"{"species":"dog","dog":{"name":"Scooby"},"human":{"name":"Zohan"}}"#);
+}
+
+#[test]
+fn preserve_discard() {
+	let mut friend: Preserving<Friend> = parse(r#"{"species":"cat","cat":"Tom","dog":{"name":"Scrappy"}}"#);
+	friend.discard_preserved();
+	assert!(json(&friend) == r#"{"species":"cat","cat":"Tom"}"#);
+	assert!(friend.into_inner() == Friend::Cat("Tom".into()));
+}
+
+#[test]
+fn preserving_null_inactive() {
+	#[derive(Debug, serde_double_tag::Deserialize, serde_double_tag::Serialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "species", null_inactive)]
+	enum Friend {
+		Human { name: String },
+		Dog { name: String },
+		Cat { name: String },
+	}
+
+	let mut friend: Preserving<Friend> = parse(r#"{"species":"dog","dog":{"name":"Scrappy"},"human":null}"#);
+	*friend = Friend::Human { name: "Zohan".into() };
+	assert!(json(&friend) == r#"{"species":"human","human":{"name":"Zohan"},"dog":{"name":"Scrappy"},"cat":null}"#);
+}
+
+/// A serializer that checks that the declared length of a struct or map matches the number of fields.
+///
+/// Returns the field names, since the JSON output does not show the declared length.
+struct CheckLength;
+
+macro_rules! unsupported {
+	($($name:ident($($type:ty),*) -> $output:ty;)*) => {
+		$(
+			fn $name(self, $(_: $type),*) -> Result<$output, Self::Error> {
+				Err(serde::ser::Error::custom("expected a struct or map"))
+			}
+		)*
+	};
+}
+
+impl serde::Serializer for CheckLength {
+	type Error = serde::de::value::Error;
+	type Ok = Vec<String>;
+	type SerializeMap = CheckLengthFields;
+	type SerializeSeq = serde::ser::Impossible<Self::Ok, Self::Error>;
+	type SerializeStruct = CheckLengthFields;
+	type SerializeStructVariant = serde::ser::Impossible<Self::Ok, Self::Error>;
+	type SerializeTuple = serde::ser::Impossible<Self::Ok, Self::Error>;
+	type SerializeTupleStruct = serde::ser::Impossible<Self::Ok, Self::Error>;
+	type SerializeTupleVariant = serde::ser::Impossible<Self::Ok, Self::Error>;
+
+	unsupported! {
+		serialize_bool(bool) -> Self::Ok;
+		serialize_i8(i8) -> Self::Ok;
+		serialize_i16(i16) -> Self::Ok;
+		serialize_i32(i32) -> Self::Ok;
+		serialize_i64(i64) -> Self::Ok;
+		serialize_u8(u8) -> Self::Ok;
+		serialize_u16(u16) -> Self::Ok;
+		serialize_u32(u32) -> Self::Ok;
+		serialize_u64(u64) -> Self::Ok;
+		serialize_f32(f32) -> Self::Ok;
+		serialize_f64(f64) -> Self::Ok;
+		serialize_char(char) -> Self::Ok;
+		serialize_str(&str) -> Self::Ok;
+		serialize_bytes(&[u8]) -> Self::Ok;
+		serialize_none() -> Self::Ok;
+		serialize_unit() -> Self::Ok;
+		serialize_unit_struct(&'static str) -> Self::Ok;
+		serialize_unit_variant(&'static str, u32, &'static str) -> Self::Ok;
+		serialize_seq(Option<usize>) -> Self::SerializeSeq;
+		serialize_tuple(usize) -> Self::SerializeTuple;
+		serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct;
+		serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant;
+		serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant;
+	}
+
+	fn serialize_some<T: serde::Serialize + ?Sized>(self, _value: &T) -> Result<Self::Ok, Self::Error> {
+		Err(serde::ser::Error::custom("expected a struct or map"))
+	}
+
+	fn serialize_newtype_struct<T: serde::Serialize + ?Sized>(
+		self,
+		_name: &'static str,
+		_value: &T,
+	) -> Result<Self::Ok, Self::Error> {
+		Err(serde::ser::Error::custom("expected a struct or map"))
+	}
+
+	fn serialize_newtype_variant<T: serde::Serialize + ?Sized>(
+		self,
+		_name: &'static str,
+		_variant_index: u32,
+		_variant: &'static str,
+		_value: &T,
+	) -> Result<Self::Ok, Self::Error> {
+		Err(serde::ser::Error::custom("expected a struct or map"))
+	}
+
+	fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+		let_assert!(Some(len) = len);
+		Ok(CheckLengthFields { len, names: Vec::new() })
+	}
+
+	fn serialize_struct(self, _name: &'static str, len: usize) -> Result<Self::SerializeStruct, Self::Error> {
+		Ok(CheckLengthFields { len, names: Vec::new() })
+	}
+}
+
+struct CheckLengthFields {
+	len: usize,
+	names: Vec<String>,
+}
+
+impl CheckLengthFields {
+	fn end(self) -> Result<Vec<String>, serde::de::value::Error> {
+		assert!(self.names.len() == self.len, "declared length does not match the fields: {:?}", self.names);
+		Ok(self.names)
+	}
+}
+
+impl serde::ser::SerializeStruct for CheckLengthFields {
+	type Error = serde::de::value::Error;
+	type Ok = Vec<String>;
+
+	fn serialize_field<T: serde::Serialize + ?Sized>(&mut self, key: &'static str, _value: &T) -> Result<(), Self::Error> {
+		self.names.push(key.into());
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		CheckLengthFields::end(self)
+	}
+}
+
+impl serde::ser::SerializeMap for CheckLengthFields {
+	type Error = serde::de::value::Error;
+	type Ok = Vec<String>;
+
+	fn serialize_key<T: serde::Serialize + ?Sized>(&mut self, key: &T) -> Result<(), Self::Error> {
+		let_assert!(Ok(serde_json::Value::String(key)) = serde_json::to_value(key));
+		self.names.push(key);
+		Ok(())
+	}
+
+	fn serialize_value<T: serde::Serialize + ?Sized>(&mut self, _value: &T) -> Result<(), Self::Error> {
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		CheckLengthFields::end(self)
+	}
+}
+
+#[test]
+fn preserving_null_inactive_length() {
+	#[derive(Debug, serde_double_tag::Deserialize, serde_double_tag::Serialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "species", null_inactive)]
+	enum Friend {
+		Human { name: String },
+		Dog { name: String },
+		#[serde(catch_all)]
+		Other { tag: String, content: Option<serde_json::Value> },
+	}
+
+	let mut friend: Preserving<Friend> = parse(r#"{"species":"dog","dog":{"name":"Scrappy"},"human":null}"#);
+	*friend = Friend::Human { name: "Zohan".into() };
+	let_assert!(Ok(names) = serde::Serialize::serialize(&friend, CheckLength));
+	assert!(names == ["species", "human", "dog"]);
+
+	// The catch_all variant is serialized as a map.
+	*friend = Friend::Other {
+		tag: "cat".into(),
+		content: None,
+	};
+	let_assert!(Ok(names) = serde::Serialize::serialize(&friend, CheckLength));
+	assert!(names == ["species", "human", "dog"]);
+	assert!(json(&friend) == r#"{"species":"cat","human":null,"dog":{"name":"Scrappy"}}"#);
+}
@@ -84,3 +84,299 @@ fn serialize_enum_generic_lifetime() {
 		}) == r#"{"type":"struct","struct":{"field_a":"bye","field_b":7}}"#
 	);
 }
+
+#[test]
+fn serialize_enum_catch_all() {
+	#[derive(serde_double_tag::Serialize, serde_double_tag::Deserialize, serde_double_tag::JsonSchema)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	enum MyEnum {
+		NewType(String),
+		#[serde(catch_all)]
+		Unknown {
+			tag: String,
+			content: Option<serde_json::Value>,
+		},
+	}
+	assert!(json(MyEnum::NewType("hello".into())) == r#"{"type":"new_type","new_type":"hello"}"#);
+	assert!(
+		json(MyEnum::Unknown {
+			tag: "cat".into(),
+			content: Some(serde_json::json!({"name": "Garfield"})),
+		}) == r#"{"type":"cat","cat":{"name":"Garfield"}}"#
+	);
+	assert!(
+		json(MyEnum::Unknown {
+			tag: "cat".into(),
+			content: None,
+		}) == r#"{"type":"cat"}"#
+	);
+}
+
+#[test]
+fn serialize_enum_content_name() {
+	#[derive(serde_double_tag::Serialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	enum MyEnum {
+		#[serde(content = "dog_data")]
+		Dog { name: String },
+	}
+	assert!(json(MyEnum::Dog { name: "Max".into() }) == r#"{"type":"dog","dog_data":{"name":"Max"}}"#);
+}
+
+#[test]
+fn serialize_enum_content_template() {
+	#[derive(serde_double_tag::Serialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	#[serde(content = "{tag}_payload")]
+	enum MyEnum {
+		NewType(String),
+		#[serde(content = "custom")]
+		Custom(u8),
+		#[serde(catch_all)]
+		Unknown(String, Option<u8>),
+	}
+	assert!(json(MyEnum::NewType("hello".into())) == r#"{"type":"new_type","new_type_payload":"hello"}"#);
+	assert!(json(MyEnum::Custom(5)) == r#"{"type":"custom","custom":5}"#);
+	assert!(json(MyEnum::Unknown("cat".into(), Some(9))) == r#"{"type":"cat","cat_payload":9}"#);
+}
+
+#[test]
+fn serialize_enum_rename_all_content() {
+	#[derive(serde_double_tag::Serialize)]
+	#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+	#[serde(rename_all_content = "camelCase")]
+	#[serde(tag = "type")]
+	enum MyEnum {
+		NewType(String),
+	}
+	assert!(json(MyEnum::NewType("hello".into())) == r#"{"type":"NEW_TYPE","newType":"hello"}"#);
+}
+
+#[test]
+fn serialize_enum_tag_value() {
+	#[derive(serde_double_tag::Serialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "kind")]
+	#[serde(discriminant_as_tag)]
+	#[repr(u8)]
+	enum MyEnum {
+		Dog {
+			name: String,
+		} = 1,
+		Cat(String),
+		#[serde(tag_value = -7)]
+		Fish = 10,
+		#[serde(tag_value = true)]
+		Bird(u8),
+	}
+	assert!(json(MyEnum::Dog { name: "Max".into() }) == r#"{"kind":1,"dog":{"name":"Max"}}"#);
+	assert!(json(MyEnum::Cat("Tom".into())) == r#"{"kind":2,"cat":"Tom"}"#);
+	assert!(json(MyEnum::Fish) == r#"{"kind":-7}"#);
+	assert!(json(MyEnum::Bird(3)) == r#"{"kind":true,"bird":3}"#);
+}
+
+#[test]
+fn serialize_enum_string_tag_value_names_content() {
+	#[derive(serde_double_tag::Serialize)]
+	#[serde(tag = "type")]
+	enum MyEnum {
+		#[serde(tag_value = "foo")]
+		Struct { a: u8 },
+		#[serde(tag_value = 5)]
+		Int(u8),
+	}
+	assert!(json(MyEnum::Struct { a: 1 }) == r#"{"type":"foo","foo":{"a":1}}"#);
+	assert!(json(MyEnum::Int(2)) == r#"{"type":5,"Int":2}"#);
+}
+
+#[test]
+fn serialize_enum_null_inactive() {
+	#[derive(serde_double_tag::Serialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type", null_inactive)]
+	enum MyEnum {
+		Unit,
+		NewType(String),
+		#[serde(content = "pair")]
+		Tuple(u8, String),
+		#[serde(catch_all)]
+		Unknown {
+			tag: String,
+			content: Option<String>,
+		},
+	}
+	assert!(json(MyEnum::Unit) == r#"{"type":"unit","new_type":null,"pair":null}"#);
+	assert!(
+		json(MyEnum::NewType("hello".into())) == r#"{"type":"new_type","new_type":"hello","unit":null,"pair":null}"#
+	);
+	assert!(
+		json(MyEnum::Tuple(3, "world".into())) == r#"{"type":"tuple","pair":[3,"world"],"unit":null,"new_type":null}"#
+	);
+	assert!(
+		json(MyEnum::Unknown {
+			tag: "other".into(),
+			content: Some("data".into())
+		}) == r#"{"type":"other","other":"data","unit":null,"new_type":null,"pair":null}"#
+	);
+}
+
+#[test]
+fn serialize_enum_unit_content() {
+	#[derive(serde_double_tag::Serialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type", unit_content = "null")]
+	enum NullUnit {
+		Unit,
+		NewType(String),
+	}
+	assert!(json(NullUnit::Unit) == r#"{"type":"unit","unit":null}"#);
+	assert!(json(NullUnit::NewType("hello".into())) == r#"{"type":"new_type","new_type":"hello"}"#);
+
+	#[derive(serde_double_tag::Serialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type", unit_content = "empty")]
+	enum EmptyUnit {
+		Unit,
+	}
+	assert!(json(EmptyUnit::Unit) == r#"{"type":"unit","unit":{}}"#);
+
+	#[derive(serde_double_tag::Serialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type", unit_content = "omit")]
+	enum OmitUnit {
+		Unit,
+	}
+	assert!(json(OmitUnit::Unit) == r#"{"type":"unit"}"#);
+}
+
+#[test]
+fn serialize_enum_empty_as_unit() {
+	#[derive(serde_double_tag::Serialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type", empty_as_unit)]
+	enum MyEnum {
+		EmptyTuple(),
+		EmptyStruct {},
+		NewType(String),
+	}
+	assert!(json(MyEnum::EmptyTuple()) == r#"{"type":"empty_tuple"}"#);
+	assert!(json(MyEnum::EmptyStruct {}) == r#"{"type":"empty_struct"}"#);
+	assert!(json(MyEnum::NewType("hello".into())) == r#"{"type":"new_type","new_type":"hello"}"#);
+
+	#[derive(serde_double_tag::Serialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type", empty_as_unit, unit_content = "null")]
+	enum NullUnit {
+		EmptyTuple(),
+		EmptyStruct {},
+	}
+	assert!(json(NullUnit::EmptyTuple()) == r#"{"type":"empty_tuple","empty_tuple":null}"#);
+	assert!(json(NullUnit::EmptyStruct {}) == r#"{"type":"empty_struct","empty_struct":null}"#);
+}
+
+#[test]
+fn serialize_enum_skip_serializing_if() {
+	#[derive(serde_double_tag::Serialize, serde_double_tag::Deserialize, serde_double_tag::AllVariants)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	enum MyEnum<T> {
+		Struct {
+			#[serde(skip_serializing_if = "Option::is_none")]
+			r#type: Option<T>,
+			#[serde(default, skip_serializing_if = "Vec::is_empty")]
+			items: Vec<u8>,
+		},
+		Tuple(u8, #[serde(skip_serializing_if = "String::is_empty")] String),
+		NewType(T),
+	}
+	assert!(
+		json(MyEnum::<u8>::Struct {
+			r#type: None,
+			items: Vec::new()
+		}) == r#"{"type":"struct","struct":{}}"#
+	);
+	assert!(
+		json(MyEnum::Struct {
+			r#type: Some(3),
+			items: vec![1]
+		}) == r#"{"type":"struct","struct":{"type":3,"items":[1]}}"#
+	);
+	assert!(json(MyEnum::<u8>::Tuple(1, String::new())) == r#"{"type":"tuple","tuple":[1]}"#);
+	assert!(json(MyEnum::<u8>::Tuple(1, "a".into())) == r#"{"type":"tuple","tuple":[1,"a"]}"#);
+
+	let all_variants = MyEnumAllVariants::from(MyEnum::<u8>::Struct {
+		r#type: None,
+		items: Vec::new(),
+	});
+	assert!(json(all_variants) == r#"{"type":"struct","struct":{}}"#);
+}
+
+#[test]
+fn serialize_enum_skip() {
+	struct Cache;
+
+	#[derive(serde_double_tag::Serialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	enum MyEnum<C> {
+		Struct {
+			id: u32,
+			#[serde(skip)]
+			cache: C,
+			#[serde(skip_serializing)]
+			hits: u32,
+		},
+		Tuple(u8, #[serde(skip_serializing)] C),
+	}
+	assert!(
+		json(MyEnum::Struct {
+			id: 1,
+			cache: Cache,
+			hits: 2
+		}) == r#"{"type":"struct","struct":{"id":1}}"#
+	);
+	assert!(json(MyEnum::Tuple(1, Cache)) == r#"{"type":"tuple","tuple":[1]}"#);
+}
+
+#[test]
+fn serialize_enum_with() {
+	mod hex {
+		pub fn serialize<S: serde::Serializer>(value: &u32, serializer: S) -> Result<S::Ok, S::Error> {
+			serializer.serialize_str(&format!("{value:x}"))
+		}
+
+		#[allow(unused)]
+		pub fn deserialize<'de, D: serde::Deserializer<'de>>(_deserializer: D) -> Result<u32, D::Error> {
+			unimplemented!()
+		}
+	}
+
+	fn serialize_len<S: serde::Serializer>(value: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_u64(value.len() as u64)
+	}
+
+	#[derive(serde_double_tag::Serialize)]
+	#[serde(rename_all = "snake_case")]
+	#[serde(tag = "type")]
+	enum MyEnum<T> {
+		Struct {
+			#[serde(with = "hex")]
+			id: u32,
+			#[serde(serialize_with = "serialize_len")]
+			data: Vec<u8>,
+			value: T,
+		},
+		Tuple(u8, #[serde(with = "hex")] u32),
+	}
+	assert!(
+		json(MyEnum::Struct {
+			id: 255,
+			data: vec![1, 2, 3],
+			value: true,
+		}) == r#"{"type":"struct","struct":{"id":"ff","data":3,"value":true}}"#
+	);
+	assert!(json(MyEnum::<bool>::Tuple(1, 16)) == r#"{"type":"tuple","tuple":[1,"10"]}"#);
+}
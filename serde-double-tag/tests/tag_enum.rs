@@ -0,0 +1,69 @@
+use assert2::{assert, let_assert};
+
+#[derive(Debug, PartialEq, serde_double_tag::TagEnum, serde_double_tag::AllVariants)]
+#[serde(rename_all = "snake_case")]
+#[serde(tag = "species", tag_enum = "FriendKind")]
+enum Friend {
+	Human {
+		name: String,
+	},
+	#[serde(alias = "doggo")]
+	Dog {
+		name: String,
+	},
+	#[serde(tag_value = 3)]
+	Cat(String),
+	Nobody,
+}
+
+#[test]
+fn tag_enum_accessor() {
+	assert!(Friend::Human { name: "Zohan".into() }.tag() == FriendKind::Human);
+	assert!(Friend::Dog { name: "Scrappy".into() }.tag() == FriendKind::Dog);
+	assert!(Friend::Cat("Tom".into()).tag() == FriendKind::Cat);
+	assert!(Friend::Nobody.tag() == FriendKind::Nobody);
+	assert!(FriendKind::ALL == [FriendKind::Human, FriendKind::Dog, FriendKind::Cat, FriendKind::Nobody]);
+}
+
+#[test]
+fn tag_enum_display_from_str() {
+	assert!(FriendKind::Human.to_string() == "human");
+	assert!(FriendKind::Cat.to_string() == "3");
+	assert!("human".parse() == Ok(FriendKind::Human));
+	assert!("doggo".parse() == Ok(FriendKind::Dog));
+	assert!("3".parse() == Ok(FriendKind::Cat));
+	let_assert!(Err(e) = "cat".parse::<FriendKind>());
+	assert!(e.to_string() == "unknown variant `cat`, expected one of `human`, `dog`, `doggo`, `3`, `nobody`");
+}
+
+#[test]
+fn tag_enum_serde() {
+	let_assert!(Ok(json) = serde_json::to_string(&FriendKind::ALL));
+	assert!(json == r#"["human","dog",3,"nobody"]"#);
+	let_assert!(Ok(kinds) = serde_json::from_str::<Vec<FriendKind>>(r#"["doggo",3]"#));
+	assert!(kinds == [FriendKind::Dog, FriendKind::Cat]);
+	let_assert!(Err(_) = serde_json::from_str::<FriendKind>("4"));
+}
+
+#[test]
+fn tag_enum_all_variants() {
+	let all = FriendAllVariants::from(Friend::Cat("Tom".into()));
+	assert!(all.species == FriendKind::Cat);
+}
+
+#[test]
+fn tag_enum_other() {
+	#[derive(serde_double_tag::TagEnum)]
+	#[serde(tag = "type", tag_enum = "ShapeKind")]
+	#[allow(unused)]
+	enum Shape {
+		Circle(f64),
+		#[serde(other)]
+		Unknown,
+	}
+
+	assert!("Circle".parse() == Ok(ShapeKind::Circle));
+	assert!("Square".parse() == Ok(ShapeKind::Unknown));
+	let_assert!(Ok(kind) = serde_json::from_str::<ShapeKind>(r#""Square""#));
+	assert!(kind == ShapeKind::Unknown);
+}
@@ -0,0 +1,95 @@
+use assert2::{assert, let_assert};
+
+#[track_caller]
+fn parse<'a, T: serde::Deserialize<'a>>(data: &'a str) -> T {
+	match serde_json::from_str(data) {
+		Ok(value) => value,
+		Err(e) => panic!("failed to parse {data}: {e}"),
+	}
+}
+
+#[track_caller]
+fn json<T: serde::Serialize>(value: T) -> String {
+	let_assert!(Ok(json) = serde_json::to_string(&value));
+	json
+}
+
+#[derive(Debug, PartialEq, serde_double_tag::Serialize, serde_double_tag::VariantStructs)]
+#[serde(rename_all = "snake_case")]
+#[serde(rename_all_fields = "camelCase")]
+#[serde(tag = "species")]
+enum Friend {
+	Human { name: String, favorite_food: String },
+	Dog { name: String, color: String },
+	Cat(String),
+}
+
+#[test]
+fn variant_structs_serde() {
+	let dog: FriendDog = parse(r#"{"name":"Scrappy","color":"white"}"#);
+	assert!(dog.name == "Scrappy");
+	assert!(dog.color == "white");
+
+	let human = FriendHuman {
+		name: "Zohan".into(),
+		favorite_food: "hummus".into(),
+	};
+	assert!(json(&human) == r#"{"name":"Zohan","favoriteFood":"hummus"}"#);
+	assert!(json(Friend::from(human)) == r#"{"species":"human","human":{"name":"Zohan","favoriteFood":"hummus"}}"#);
+}
+
+#[test]
+fn variant_structs_conversions() {
+	let friend = Friend::from(FriendDog {
+		name: "Scrappy".into(),
+		color: "white".into(),
+	});
+	assert!(
+		friend
+			== Friend::Dog {
+				name: "Scrappy".into(),
+				color: "white".into()
+			}
+	);
+
+	let_assert!(Ok(dog) = FriendDog::try_from(friend));
+	assert!(dog.name == "Scrappy");
+	assert!(dog.color == "white");
+
+	let_assert!(Err(friend) = FriendHuman::try_from(Friend::Cat("Tom".into())));
+	assert!(friend == Friend::Cat("Tom".into()));
+}
+
+#[test]
+fn variant_structs_generics() {
+	#[derive(serde_double_tag::VariantStructs)]
+	#[serde(tag = "type")]
+	#[allow(unused)]
+	enum Message<T, U> {
+		Data { value: T },
+		Other { value: U },
+	}
+
+	let_assert!(Ok(data) = MessageData::try_from(Message::<u32, String>::Data { value: 5 }));
+	assert!(data.value == 5);
+	let message: Message<u32, String> = MessageData { value: 5 }.into();
+	assert!(let Message::Data { value: 5 } = message);
+}
+
+#[test]
+fn variant_structs_without_json_schema() {
+	// Not a `JsonSchema` type: the generated structs only derive `JsonSchema` with `#[serde(derive_json_schema)]`.
+	#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+	struct Name(String);
+
+	#[derive(serde_double_tag::VariantStructs)]
+	#[serde(tag = "type")]
+	#[allow(unused)]
+	enum Pet {
+		Dog { name: Name },
+	}
+
+	let dog: PetDog = parse(r#"{"name":"Scrappy"}"#);
+	assert!(dog.name == Name("Scrappy".into()));
+	assert!(json(&dog) == r#"{"name":"Scrappy"}"#);
+}